@@ -0,0 +1,484 @@
+//! Derive macros for [minio](https://docs.rs/minio), re-exported from there with the `derive`
+//! feature enabled. Use those rather than depending on this crate directly.
+//!
+//! Fields are read and written in declaration order. Supported field types are primitive
+//! numbers (`u8`..`u128`, `i8`..`i128`, `f32`, `f64`), arrays of them, `String`, `Vec<T>` of
+//! primitives or other derived types, and any type implementing `MinioRead`/`MinioWrite`.
+//!
+//! Attributes, all within `#[minio(...)]`:
+//! - `le`, `be`, `ne`: byte order of a field, or of every field if placed on the struct.
+//!   Multi-byte numbers must have one or the other.
+//! - `cstr`: the field is a null-terminated UTF-8 `String`.
+//! - `prefix = u8 | u16_le | u16_be | u32_le | u32_be | uleb128`: the field is a
+//!   length-prefixed UTF-8 `String`.
+//! - `max = N`: maximum length for `cstr` and `prefix` strings, in bytes.
+//! - `count = field`: the field is a `Vec` whose length is stored in an earlier field.
+//! - `pad = N`, `pad_after = N`: N bytes of zero padding before or after the field.
+//!
+//! `FixedSize` implements `minio::FixedSize` for structs whose fields all have a fixed size
+//! (numbers, arrays of them, other `FixedSize` types, and padding), giving their size in bytes
+//! as `SIZE`. It takes the same attributes, so it can sit alongside the other two.
+
+extern crate proc_macro;
+
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, GenericArgument,
+    Ident, LitInt, PathArguments, Result, Type,
+};
+
+#[derive(Clone, Copy)]
+enum Endian {
+    Le,
+    Be,
+    Ne,
+}
+
+impl Endian {
+    fn suffix(self) -> &'static str {
+        match self {
+            Endian::Le => "le",
+            Endian::Be => "be",
+            Endian::Ne => "ne",
+        }
+    }
+}
+
+enum Str {
+    Cstr,
+    Prefix(Ident),
+}
+
+#[derive(Default)]
+struct Attrs {
+    endian: Option<Endian>,
+    string: Option<Str>,
+    max: Option<LitInt>,
+    count: Option<Ident>,
+    pad: Option<LitInt>,
+    pad_after: Option<LitInt>,
+}
+
+impl Attrs {
+    fn parse(attrs: &[syn::Attribute]) -> Result<Self> {
+        let mut out = Attrs::default();
+        for attr in attrs.iter().filter(|a| a.path().is_ident("minio")) {
+            attr.parse_nested_meta(|meta| {
+                let path = &meta.path;
+                if path.is_ident("le") {
+                    out.endian = Some(Endian::Le);
+                } else if path.is_ident("be") {
+                    out.endian = Some(Endian::Be);
+                } else if path.is_ident("ne") {
+                    out.endian = Some(Endian::Ne);
+                } else if path.is_ident("cstr") {
+                    out.string = Some(Str::Cstr);
+                } else if path.is_ident("prefix") {
+                    let prefix: Ident = meta.value()?.parse()?;
+                    let variant = match prefix.to_string().as_str() {
+                        "u8" => "U8",
+                        "u16_le" => "U16Le",
+                        "u16_be" => "U16Be",
+                        "u32_le" => "U32Le",
+                        "u32_be" => "U32Be",
+                        "uleb128" => "Uleb128",
+                        _ => return Err(Error::new(prefix.span(), "unknown length prefix")),
+                    };
+                    out.string = Some(Str::Prefix(Ident::new(variant, prefix.span())));
+                } else if path.is_ident("max") {
+                    out.max = Some(meta.value()?.parse()?);
+                } else if path.is_ident("count") {
+                    out.count = Some(meta.value()?.parse()?);
+                } else if path.is_ident("pad") {
+                    out.pad = Some(meta.value()?.parse()?);
+                } else if path.is_ident("pad_after") {
+                    out.pad_after = Some(meta.value()?.parse()?);
+                } else {
+                    return Err(meta.error("unknown minio attribute"));
+                }
+                Ok(())
+            })?;
+        }
+        Ok(out)
+    }
+}
+
+const PRIMITIVES: &[&str] = &[
+    "u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128", "f32", "f64",
+];
+
+/// Returns the primitive's name if `ty` is one of `PRIMITIVES`.
+fn primitive(ty: &Type) -> Option<String> {
+    match ty {
+        Type::Path(p) if p.qself.is_none() => p
+            .path
+            .get_ident()
+            .map(Ident::to_string)
+            .filter(|name| PRIMITIVES.contains(&name.as_str())),
+        _ => None,
+    }
+}
+
+/// Returns `T` if `ty` is `Vec<T>`.
+fn vec_element(ty: &Type) -> Option<&Type> {
+    let p = match ty {
+        Type::Path(p) if p.qself.is_none() => p,
+        _ => return None,
+    };
+    let last = p.path.segments.last()?;
+    if last.ident != "Vec" {
+        return None;
+    }
+    match &last.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_string(ty: &Type) -> bool {
+    match ty {
+        Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "String"),
+        _ => false,
+    }
+}
+
+struct Field {
+    /// How the field is accessed on `self` (`name` or `0`).
+    member: TokenStream,
+    /// Local variable the field is read into, prefixed so it can't clash with anything else
+    /// in the generated code.
+    local: Ident,
+    ty: Type,
+    attrs: Attrs,
+    endian: Option<Endian>,
+}
+
+impl Field {
+    fn endian(&self, span: Span) -> Result<Endian> {
+        self.endian.ok_or_else(|| {
+            Error::new(span, "multi-byte numbers need a byte order: #[minio(le)], `be` or `ne`")
+        })
+    }
+
+    /// Name of the `ReadPrimitives`/`WritePrimitives` method for a primitive, minus the prefix.
+    fn primitive_method(&self, prim: &str, span: Span) -> Result<String> {
+        if prim == "u8" || prim == "i8" {
+            Ok(prim.to_owned())
+        } else {
+            Ok(format!("{}_{}", prim, self.endian(span)?.suffix()))
+        }
+    }
+
+    fn read_element(&self, ty: &Type) -> Result<TokenStream> {
+        match primitive(ty) {
+            Some(prim) => {
+                let method = format_ident!("read_{}", self.primitive_method(&prim, ty.span())?);
+                Ok(quote_spanned!(ty.span()=> ::minio::ReadPrimitives::#method(rdr)?))
+            },
+            None => Ok(quote_spanned!(ty.span()=> <#ty as ::minio::MinioRead>::minio_read(rdr)?)),
+        }
+    }
+
+    fn write_element(&self, ty: &Type, val: TokenStream) -> Result<TokenStream> {
+        match primitive(ty) {
+            Some(prim) => {
+                let method = format_ident!("write_{}", self.primitive_method(&prim, ty.span())?);
+                Ok(quote_spanned!(ty.span()=> ::minio::WritePrimitives::#method(wtr, #val)?))
+            },
+            None => Ok(quote_spanned!(ty.span()=> ::minio::MinioWrite::minio_write(#val, wtr)?)),
+        }
+    }
+
+    fn max(&self) -> TokenStream {
+        match &self.attrs.max {
+            Some(max) => quote!(::core::option::Option::Some(#max)),
+            None => quote!(::core::option::Option::None),
+        }
+    }
+
+    fn read(&self) -> Result<TokenStream> {
+        let ty = &self.ty;
+        let io = quote!(::minio::__private::io);
+        if let Some(string) = &self.attrs.string {
+            let max = self.max();
+            return Ok(match string {
+                Str::Cstr => quote! {
+                    ::minio::ReadStrings::read_cstr::<::minio::Utf8>(rdr, #max)?
+                },
+                Str::Prefix(prefix) => quote! {
+                    ::minio::ReadStrings::read_pstr_utf8(
+                        rdr,
+                        ::minio::LengthPrefix::#prefix,
+                        #max,
+                    )?
+                },
+            });
+        }
+        if let Some(count) = &self.attrs.count {
+            let elem = vec_element(ty)
+                .ok_or_else(|| Error::new(ty.span(), "`count` can only be used on a `Vec`"))?;
+            let read = self.read_element(elem)?;
+            let local = _local(count);
+            return Ok(quote! {{
+                let count = <usize as ::core::convert::TryFrom<_>>::try_from(#local)
+                    .map_err(|_| {
+                        #io::Error::new(#io::ErrorKind::InvalidData, "count out of range")
+                    })?;
+                let mut vec = ::minio::__private::Vec::new();
+                for _ in 0..count {
+                    vec.push(#read);
+                }
+                vec
+            }});
+        }
+        if let Type::Array(array) = ty {
+            if primitive(&array.elem).is_some() {
+                let (elem, len) = (&array.elem, &array.len);
+                let read = self.read_element(elem)?;
+                return Ok(quote! {{
+                    let mut array = [0 as #elem; #len];
+                    for x in array.iter_mut() {
+                        *x = #read;
+                    }
+                    array
+                }});
+            }
+        }
+        if is_string(ty) {
+            let msg = "strings need #[minio(cstr)] or #[minio(prefix = ...)]";
+            return Err(Error::new(ty.span(), msg));
+        }
+        if vec_element(ty).is_some() {
+            return Err(Error::new(ty.span(), "`Vec`s need #[minio(count = field)]"));
+        }
+        self.read_element(ty)
+    }
+
+    fn write(&self) -> Result<TokenStream> {
+        let ty = &self.ty;
+        let member = &self.member;
+        let io = quote!(::minio::__private::io);
+        if let Some(string) = &self.attrs.string {
+            let max = self.max();
+            return Ok(match string {
+                Str::Cstr => quote! {
+                    ::minio::WriteStrings::write_cstr_utf8(wtr, &self.#member, #max)?
+                },
+                Str::Prefix(prefix) => quote! {
+                    ::minio::WriteStrings::write_pstr_utf8(
+                        wtr,
+                        &self.#member,
+                        ::minio::LengthPrefix::#prefix,
+                        #max,
+                    )?
+                },
+            });
+        }
+        if let Some(count) = &self.attrs.count {
+            let elem = vec_element(ty)
+                .ok_or_else(|| Error::new(ty.span(), "`count` can only be used on a `Vec`"))?;
+            let write = match primitive(elem) {
+                Some(_) => self.write_element(elem, quote!(*x))?,
+                None => self.write_element(elem, quote!(x))?,
+            };
+            return Ok(quote! {{
+                let count = <usize as ::core::convert::TryFrom<_>>::try_from(self.#count);
+                if count != ::core::result::Result::Ok(self.#member.len()) {
+                    return ::core::result::Result::Err(#io::Error::new(
+                        #io::ErrorKind::InvalidInput,
+                        "count doesn't match length",
+                    ));
+                }
+                let mut written = 0;
+                for x in self.#member.iter() {
+                    written += #write;
+                }
+                written
+            }});
+        }
+        if let Type::Array(array) = ty {
+            if primitive(&array.elem).is_some() {
+                let write = self.write_element(&array.elem, quote!(*x))?;
+                return Ok(quote! {{
+                    let mut written = 0;
+                    for x in self.#member.iter() {
+                        written += #write;
+                    }
+                    written
+                }});
+            }
+        }
+        match primitive(ty) {
+            Some(_) => self.write_element(ty, quote!(self.#member)),
+            None => self.write_element(ty, quote!(&self.#member)),
+        }
+    }
+
+    /// The field's size in bytes, including padding.
+    fn size(&self) -> Result<TokenStream> {
+        let ty = &self.ty;
+        if self.attrs.string.is_some() || self.attrs.count.is_some() {
+            return Err(Error::new(ty.span(), "strings and `Vec`s don't have a fixed size"));
+        }
+        let (_, _, pad) = padding(&self.attrs.pad);
+        let (_, _, pad_after) = padding(&self.attrs.pad_after);
+        Ok(quote_spanned!(ty.span()=> #pad + <#ty as ::minio::FixedSize>::SIZE + #pad_after))
+    }
+}
+
+/// The local variable a named field is read into.
+fn _local(ident: &Ident) -> Ident {
+    format_ident!("__minio_{}", ident, span = ident.span())
+}
+
+fn padding(pad: &Option<LitInt>) -> (TokenStream, TokenStream, TokenStream) {
+    match pad {
+        Some(pad) => (
+            quote!(rdr.read_exact(&mut [0u8; #pad])?;),
+            quote!(wtr.write_all(&[0u8; #pad])?; written += #pad;),
+            quote!(#pad),
+        ),
+        None => (quote!(), quote!(), quote!(0)),
+    }
+}
+
+fn fields(input: &DeriveInput) -> Result<(Vec<Field>, bool)> {
+    let data = match &input.data {
+        Data::Struct(data) => data,
+        _ => return Err(Error::new(input.span(), "minio can only derive for structs")),
+    };
+    let container = Attrs::parse(&input.attrs)?;
+    let named = matches!(data.fields, Fields::Named(_));
+    let fields = data
+        .fields
+        .iter()
+        .enumerate()
+        .map(|(i, field)| {
+            let attrs = Attrs::parse(&field.attrs)?;
+            let (member, local) = match &field.ident {
+                Some(ident) => (quote!(#ident), _local(ident)),
+                None => {
+                    let index = syn::Index::from(i);
+                    (quote!(#index), format_ident!("__minio_{}", i))
+                },
+            };
+            if attrs.count.is_some() && !named {
+                return Err(Error::new(field.span(), "`count` needs named fields"));
+            }
+            Ok(Field {
+                member,
+                local,
+                ty: field.ty.clone(),
+                endian: attrs.endian.or(container.endian),
+                attrs,
+            })
+        })
+        .collect::<Result<_>>()?;
+    Ok((fields, named))
+}
+
+fn expand_read(input: DeriveInput) -> Result<TokenStream> {
+    let (fields, named) = fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut body = Vec::new();
+    for field in &fields {
+        let local = &field.local;
+        let read = field.read()?;
+        let (pad, _, _) = padding(&field.attrs.pad);
+        let (pad_after, _, _) = padding(&field.attrs.pad_after);
+        body.push(quote!(#pad let #local = #read; #pad_after));
+    }
+    let locals = fields.iter().map(|f| &f.local);
+    let construct = if named {
+        let members = fields.iter().map(|f| &f.member);
+        quote!(#name { #(#members: #locals),* })
+    } else {
+        quote!(#name ( #(#locals),* ))
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::minio::MinioRead for #name #ty_generics #where_clause {
+            #[allow(unused_imports)]
+            fn minio_read<R: ::minio::__private::io::Read>(
+                rdr: &mut R,
+            ) -> ::minio::__private::io::Result<Self> {
+                use ::minio::__private::io::Read;
+                #(#body)*
+                ::core::result::Result::Ok(#construct)
+            }
+        }
+    })
+}
+
+fn expand_write(input: DeriveInput) -> Result<TokenStream> {
+    let (fields, _) = fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut body = Vec::new();
+    for field in &fields {
+        let write = field.write()?;
+        let (_, pad, _) = padding(&field.attrs.pad);
+        let (_, pad_after, _) = padding(&field.attrs.pad_after);
+        body.push(quote!(#pad written += #write; #pad_after));
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::minio::MinioWrite for #name #ty_generics #where_clause {
+            #[allow(unused_imports, unused_mut)]
+            fn minio_write<W: ::minio::__private::io::Write>(
+                &self,
+                wtr: &mut W,
+            ) -> ::minio::__private::io::Result<usize> {
+                use ::minio::__private::io::Write;
+                let mut written = 0;
+                #(#body)*
+                ::core::result::Result::Ok(written)
+            }
+        }
+    })
+}
+
+fn expand_fixed_size(input: DeriveInput) -> Result<TokenStream> {
+    let (fields, _) = fields(&input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let sizes = fields.iter().map(Field::size).collect::<Result<Vec<_>>>()?;
+
+    Ok(quote! {
+        impl #impl_generics ::minio::FixedSize for #name #ty_generics #where_clause {
+            const SIZE: usize = 0 #(+ #sizes)*;
+        }
+    })
+}
+
+/// Derives `minio::MinioRead`. See the [crate documentation](index.html) for attributes.
+#[proc_macro_derive(MinioRead, attributes(minio))]
+pub fn derive_read(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_read(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `minio::MinioWrite`. See the [crate documentation](index.html) for attributes.
+#[proc_macro_derive(MinioWrite, attributes(minio))]
+pub fn derive_write(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_write(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Derives `minio::FixedSize`. See the [crate documentation](index.html) for attributes.
+#[proc_macro_derive(FixedSize, attributes(minio))]
+pub fn derive_fixed_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    expand_fixed_size(parse_macro_input!(input as DeriveInput))
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
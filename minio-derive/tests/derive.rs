@@ -0,0 +1,126 @@
+use minio::{FixedSize, MinioRead, MinioWrite};
+use std::io::Cursor;
+
+#[derive(Debug, PartialEq, MinioRead, MinioWrite, FixedSize)]
+#[minio(le)]
+struct Header {
+    magic: [u8; 4],
+    version: u16,
+    #[minio(be)]
+    flags: u32,
+    #[minio(pad = 2)]
+    scale: f32,
+    rgb: [u16; 3],
+}
+
+#[derive(Debug, PartialEq, MinioRead, MinioWrite, FixedSize)]
+struct Point(#[minio(be)] i16, #[minio(be)] i16);
+
+#[derive(Debug, PartialEq, MinioRead, MinioWrite)]
+#[minio(le)]
+struct File {
+    header: Header,
+    #[minio(cstr, max = 16)]
+    name: String,
+    #[minio(prefix = u16_be)]
+    comment: String,
+    point_count: u8,
+    #[minio(count = point_count, pad_after = 1)]
+    points: Vec<Point>,
+    sample_count: u32,
+    #[minio(count = sample_count)]
+    samples: Vec<i16>,
+}
+
+// field names which the generated code uses for its own variables
+#[derive(Debug, PartialEq, MinioRead, MinioWrite)]
+#[minio(le)]
+struct Shadowing {
+    rdr: u16,
+    count: u8,
+    #[minio(count = count)]
+    vec: Vec<u8>,
+    written: u32,
+}
+
+// fixed-size fields of other derived types count towards the size too
+#[derive(Debug, PartialEq, MinioRead, MinioWrite, FixedSize)]
+struct Record {
+    header: Header,
+    #[minio(pad_after = 2)]
+    tag: [u8; 3],
+    point: Point,
+}
+
+fn file() -> File {
+    File {
+        header: Header {
+            magic: *b"TEST",
+            version: 2,
+            flags: 0xDEADBEEF,
+            scale: 1.5,
+            rgb: [1, 2, 3],
+        },
+        name: "minio".to_owned(),
+        comment: "hi".to_owned(),
+        point_count: 2,
+        points: vec![Point(-1, 1), Point(300, -300)],
+        sample_count: 3,
+        samples: vec![-2, 0, 2],
+    }
+}
+
+#[test]
+fn read_write() {
+    let mut buf = vec![];
+    let written = file().minio_write(&mut buf).unwrap();
+    assert_eq!(written, buf.len());
+    assert_eq!(Header::SIZE, 22);
+    assert_eq!(Point::SIZE, 4);
+    assert_eq!(Record::SIZE, 31);
+    let record = Record { header: file().header, tag: [1, 2, 3], point: Point(4, 5) };
+    assert_eq!(Record::SIZE, record.minio_write(&mut vec![]).unwrap());
+
+    #[rustfmt::skip]
+    let expected: &[u8] = &[
+        b'T', b'E', b'S', b'T', 0x02, 0x00, 0xDE, 0xAD, 0xBE, 0xEF, 0x00, 0x00,
+        0x00, 0x00, 0xC0, 0x3F, 0x01, 0x00, 0x02, 0x00, 0x03, 0x00,
+        b'm', b'i', b'n', b'i', b'o', 0x00,
+        0x00, 0x02, b'h', b'i',
+        0x02, 0xFF, 0xFF, 0x00, 0x01, 0x01, 0x2C, 0xFE, 0xD4, 0x00,
+        0x03, 0x00, 0x00, 0x00, 0xFE, 0xFF, 0x00, 0x00, 0x02, 0x00,
+    ];
+    assert_eq!(expected, &*buf);
+
+    let mut rdr = Cursor::new(&buf);
+    assert_eq!(file(), File::minio_read(&mut rdr).unwrap());
+    assert_eq!(buf.len() as u64, rdr.position());
+}
+
+#[test]
+fn errors() {
+    // count field doesn't match
+    let mut bad = file();
+    bad.sample_count = 4;
+    assert!(bad.minio_write(&mut vec![]).is_err());
+
+    // string too long
+    let mut bad = file();
+    bad.name = "a".repeat(17);
+    assert!(bad.minio_write(&mut vec![]).is_err());
+
+    // truncated
+    let mut buf = vec![];
+    file().minio_write(&mut buf).unwrap();
+    buf.pop();
+    assert!(File::minio_read(&mut Cursor::new(&buf)).is_err());
+}
+
+#[test]
+fn shadowing() {
+    let value = Shadowing { rdr: 0x1234, count: 2, vec: vec![5, 6], written: 7 };
+    let mut buf = vec![];
+    assert_eq!(9, value.minio_write(&mut buf).unwrap());
+    assert_eq!(b"\x34\x12\x02\x05\x06\x07\x00\x00\x00", &*buf);
+    assert_eq!(value, Shadowing::minio_read(&mut Cursor::new(&buf)).unwrap());
+}
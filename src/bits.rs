@@ -0,0 +1,219 @@
+use crate::{io, ReadPrimitives, WritePrimitives};
+
+/// The order bits are packed into each byte in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// Bits are taken from the most significant end of each byte first, and values are
+    /// stored most significant bit first (JPEG, MPEG, most Huffman streams).
+    MsbFirst,
+
+    /// Bits are taken from the least significant end of each byte first, and values are
+    /// stored least significant bit first (DEFLATE, GIF's LZW).
+    LsbFirst,
+}
+
+fn mask(bits: u32) -> u64 {
+    if bits == 64 { !0 } else { (1 << bits) - 1 }
+}
+
+/// Reads values of arbitrary bit widths from the underlying reader.
+///
+/// Bytes are only read from the underlying reader when bits from them are needed.
+pub struct BitReader<R> {
+    inner: R,
+    order: BitOrder,
+    byte: u8,
+    left: u32,
+}
+
+impl<R> BitReader<R>
+where
+    R: io::Read,
+{
+    /// Creates a new bit reader over `inner`, starting at a byte boundary.
+    pub fn new(inner: R, order: BitOrder) -> Self {
+        Self { inner, order, byte: 0, left: 0 }
+    }
+
+    /// Reads a single bit.
+    pub fn read_bit(&mut self) -> io::Result<bool> {
+        self.read_bits(1).map(|x| x != 0)
+    }
+
+    /// Reads an unsigned value `n` bits wide.
+    ///
+    /// # Panics
+    /// Panics if `n` is greater than 64.
+    pub fn read_bits(&mut self, mut n: u32) -> io::Result<u64> {
+        assert!(n <= 64, "can't read more than 64 bits at once");
+        let mut value = 0u64;
+        let mut shift = 0;
+        while n > 0 {
+            if self.left == 0 {
+                self.byte = self.inner.read_u8()?;
+                self.left = 8;
+            }
+            let take = n.min(self.left);
+            let bits = match self.order {
+                BitOrder::MsbFirst => u64::from(self.byte >> (self.left - take)) & mask(take),
+                BitOrder::LsbFirst => u64::from(self.byte >> (8 - self.left)) & mask(take),
+            };
+            match self.order {
+                BitOrder::MsbFirst => value = (value << take) | bits,
+                BitOrder::LsbFirst => value |= bits << shift,
+            }
+            shift += take;
+            self.left -= take;
+            n -= take;
+        }
+        Ok(value)
+    }
+
+    /// Reads a two's complement signed value `n` bits wide.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 64.
+    pub fn read_bits_signed(&mut self, n: u32) -> io::Result<i64> {
+        assert!(n > 0 && n <= 64, "can't read a signed value 0 or over 64 bits wide");
+        let shift = 64 - n;
+        self.read_bits(n).map(|x| ((x << shift) as i64) >> shift)
+    }
+
+    /// Whether the next bit read is at the start of a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.left == 0
+    }
+
+    /// Discards the remaining bits of the current byte, if any.
+    pub fn align_to_byte(&mut self) {
+        self.left = 0;
+    }
+
+    /// Aligns to the next byte boundary (see [align_to_byte](#method.align_to_byte)) and
+    /// returns the underlying reader, so the rest can be read through `ReadPrimitives` or
+    /// `ReadStrings`. Bit reading continues from wherever that leaves off.
+    pub fn aligned(&mut self) -> &mut R {
+        self.align_to_byte();
+        &mut self.inner
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns the underlying reader, discarding the remaining bits of the current byte.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+/// Writes values of arbitrary bit widths to the underlying writer.
+///
+/// Bytes are written to the underlying writer once they're full, so the last partial byte
+/// must be padded out with [align_to_byte](#method.align_to_byte) or
+/// [into_inner](#method.into_inner), otherwise it's lost when this is dropped.
+pub struct BitWriter<W> {
+    inner: W,
+    order: BitOrder,
+    byte: u8,
+    filled: u32,
+}
+
+impl<W> BitWriter<W>
+where
+    W: io::Write,
+{
+    /// Creates a new bit writer over `inner`, starting at a byte boundary.
+    pub fn new(inner: W, order: BitOrder) -> Self {
+        Self { inner, order, byte: 0, filled: 0 }
+    }
+
+    /// Writes a single bit.
+    pub fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.write_bits(1, u64::from(bit))
+    }
+
+    /// Writes an unsigned value `n` bits wide.
+    ///
+    /// Values which don't fit in `n` bits are rejected with `io::ErrorKind::InvalidInput`.
+    ///
+    /// # Panics
+    /// Panics if `n` is greater than 64.
+    pub fn write_bits(&mut self, n: u32, value: u64) -> io::Result<()> {
+        assert!(n <= 64, "can't write more than 64 bits at once");
+        if value & !mask(n) != 0 {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "value doesn't fit in bits"));
+        }
+        let mut done = 0;
+        while done < n {
+            let take = (n - done).min(8 - self.filled);
+            let bits = match self.order {
+                BitOrder::MsbFirst => (value >> (n - done - take)) & mask(take),
+                BitOrder::LsbFirst => (value >> done) & mask(take),
+            };
+            match self.order {
+                BitOrder::MsbFirst => self.byte |= (bits as u8) << (8 - self.filled - take),
+                BitOrder::LsbFirst => self.byte |= (bits as u8) << self.filled,
+            }
+            self.filled += take;
+            done += take;
+            if self.filled == 8 {
+                self.inner.write_u8(self.byte)?;
+                self.byte = 0;
+                self.filled = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Writes a two's complement signed value `n` bits wide.
+    ///
+    /// Values which don't fit in `n` bits are rejected with `io::ErrorKind::InvalidInput`.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 64.
+    pub fn write_bits_signed(&mut self, n: u32, value: i64) -> io::Result<()> {
+        assert!(n > 0 && n <= 64, "can't write a signed value 0 or over 64 bits wide");
+        let shift = 64 - n;
+        if (value << shift) >> shift != value {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "value doesn't fit in bits"));
+        }
+        self.write_bits(n, value as u64 & mask(n))
+    }
+
+    /// Whether the next bit written is at the start of a byte.
+    pub fn is_aligned(&self) -> bool {
+        self.filled == 0
+    }
+
+    /// Pads the current byte out with zero bits and writes it, if there is one.
+    pub fn align_to_byte(&mut self) -> io::Result<()> {
+        if self.filled != 0 {
+            self.inner.write_u8(self.byte)?;
+            self.byte = 0;
+            self.filled = 0;
+        }
+        Ok(())
+    }
+
+    /// Aligns to the next byte boundary (see [align_to_byte](#method.align_to_byte)) and
+    /// returns the underlying writer, so the rest can be written through `WritePrimitives` or
+    /// `WriteStrings`. Bit writing continues from wherever that leaves off.
+    pub fn aligned(&mut self) -> io::Result<&mut W> {
+        self.align_to_byte()?;
+        Ok(&mut self.inner)
+    }
+
+    /// Returns a reference to the underlying writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Pads out the current byte (see [align_to_byte](#method.align_to_byte)) and returns the
+    /// underlying writer.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.align_to_byte()?;
+        Ok(self.inner)
+    }
+}
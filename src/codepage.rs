@@ -0,0 +1,66 @@
+use core::convert::TryFrom;
+
+// Single-byte legacy code pages, each as a pair of functions mapping a byte to a `char`
+// and back. Every byte decodes to something, so only encoding can fail.
+
+/// Windows-1252 bytes 0x80 to 0x9F. The five bytes Windows leaves undefined map to the C1
+/// control characters with the same value, as in the WHATWG Encoding Standard, so any byte
+/// string survives decoding and encoding again.
+const CP1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// Code page 437 bytes 0x80 to 0xFF. Bytes below that are treated as ASCII,
+/// rather than as the symbols the IBM PC displayed for control characters.
+const CP437_HIGH: [char; 128] = [
+    'Ç', 'ü', 'é', 'â', 'ä', 'à', 'å', 'ç', 'ê', 'ë', 'è', 'ï', 'î', 'ì', 'Ä', 'Å',
+    'É', 'æ', 'Æ', 'ô', 'ö', 'ò', 'û', 'ù', 'ÿ', 'Ö', 'Ü', '¢', '£', '¥', '₧', 'ƒ',
+    'á', 'í', 'ó', 'ú', 'ñ', 'Ñ', 'ª', 'º', '¿', '⌐', '¬', '½', '¼', '¡', '«', '»',
+    '░', '▒', '▓', '│', '┤', '╡', '╢', '╖', '╕', '╣', '║', '╗', '╝', '╜', '╛', '┐',
+    '└', '┴', '┬', '├', '─', '┼', '╞', '╟', '╚', '╔', '╩', '╦', '╠', '═', '╬', '╧',
+    '╨', '╤', '╥', '╙', '╘', '╒', '╓', '╫', '╪', '┘', '┌', '█', '▄', '▌', '▐', '▀',
+    'α', 'ß', 'Γ', 'π', 'Σ', 'σ', 'µ', 'τ', 'Φ', 'Θ', 'Ω', 'δ', '∞', 'φ', 'ε', '∩',
+    '≡', '±', '≥', '≤', '⌠', '⌡', '÷', '≈', '°', '∙', '·', '√', 'ⁿ', '²', '■', '\u{00A0}',
+];
+
+#[cfg(feature = "alloc")]
+pub(crate) fn latin1_decode(byte: u8) -> char {
+    char::from(byte)
+}
+
+pub(crate) fn latin1_encode(c: char) -> Option<u8> {
+    u8::try_from(u32::from(c)).ok()
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn cp1252_decode(byte: u8) -> char {
+    match byte {
+        0x80..=0x9F => CP1252_HIGH[usize::from(byte - 0x80)],
+        _ => char::from(byte),
+    }
+}
+
+pub(crate) fn cp1252_encode(c: char) -> Option<u8> {
+    match u32::from(c) {
+        x @ 0x00..=0x7F | x @ 0xA0..=0xFF => Some(x as u8),
+        _ => CP1252_HIGH.iter().position(|&x| x == c).map(|i| 0x80 + i as u8),
+    }
+}
+
+#[cfg(feature = "alloc")]
+pub(crate) fn cp437_decode(byte: u8) -> char {
+    match byte {
+        0x80..=0xFF => CP437_HIGH[usize::from(byte - 0x80)],
+        _ => char::from(byte),
+    }
+}
+
+pub(crate) fn cp437_encode(c: char) -> Option<u8> {
+    match u32::from(c) {
+        x @ 0x00..=0x7F => Some(x as u8),
+        _ => CP437_HIGH.iter().position(|&x| x == c).map(|i| 0x80 + i as u8),
+    }
+}
@@ -0,0 +1,176 @@
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, string::String};
+use core::str;
+
+macro_rules! _cursor_impl {
+    // Used for i8 and u8, as they are endian independent.
+    ($t: ty, $name: literal, $fn: ident) => {
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name] #[doc = ", or returns `None` if there's not enough left."]
+        pub fn $fn(&mut self) -> Option<$t> {
+            self.read_array().map(<$t>::from_ne_bytes)
+        }
+    };
+
+    ($t: ty, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+        _cursor_impl!($t, core::convert::identity, $t, $name, $le, $be, $ne);
+    };
+
+    ($read_t: ty, $map: expr, $ret_t: ty, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "(little-endian), or returns `None` if there's not enough left."]
+        pub fn $le(&mut self) -> Option<$ret_t> {
+            self.read_array().map(<$read_t>::from_le_bytes).map($map)
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "(big-endian), or returns `None` if there's not enough left."]
+        pub fn $be(&mut self) -> Option<$ret_t> {
+            self.read_array().map(<$read_t>::from_be_bytes).map($map)
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "(native-endian), or returns `None` if there's not enough left."]
+        pub fn $ne(&mut self) -> Option<$ret_t> {
+            self.read_array().map(<$read_t>::from_ne_bytes).map($map)
+        }
+    };
+}
+
+/// A cursor over a byte slice, reading the same primitives as
+/// [ReadPrimitives](trait.ReadPrimitives.html) without going through `io::Read`,
+/// and borrowing strings from the slice instead of allocating them.
+///
+/// Running out of input returns `None` and leaves the position where it was.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ByteCursor<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+#[rustfmt::skip]
+impl<'a> ByteCursor<'a> {
+    _cursor_impl!(i8, "an `i8`", read_i8);
+    _cursor_impl!(u8, "a `u8`", read_u8);
+    _cursor_impl!(i8, "an `i8`", read_i8_le, read_i8_be, read_i8_ne);
+    _cursor_impl!(u8, "a `u8`", read_u8_le, read_u8_be, read_u8_ne);
+    _cursor_impl!(i16, "an `i16`", read_i16_le, read_i16_be, read_i16_ne);
+    _cursor_impl!(u16, "a `u16`", read_u16_le, read_u16_be, read_u16_ne);
+    _cursor_impl!(i32, "an `i32`", read_i32_le, read_i32_be, read_i32_ne);
+    _cursor_impl!(u32, "a `u32`", read_u32_le, read_u32_be, read_u32_ne);
+    _cursor_impl!(i64, "an `i64`", read_i64_le, read_i64_be, read_i64_ne);
+    _cursor_impl!(u64, "a `u64`", read_u64_le, read_u64_be, read_u64_ne);
+    _cursor_impl!(i128, "an `i128`", read_i128_le, read_i128_be, read_i128_ne);
+    _cursor_impl!(u128, "a `u128`", read_u128_le, read_u128_be, read_u128_ne);
+    _cursor_impl!(u32, f32::from_bits, f32, "an `f32`", read_f32_le, read_f32_be, read_f32_ne);
+    _cursor_impl!(u64, f64::from_bits, f64, "an `f64`", read_f64_le, read_f64_be, read_f64_ne);
+}
+
+impl<'a> ByteCursor<'a> {
+    /// Creates a new cursor at the start of `data`.
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Returns the current offset into the slice.
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// Sets the current offset into the slice. It may be past the end,
+    /// in which case there's nothing left to read.
+    pub fn set_position(&mut self, pos: usize) {
+        self.pos = pos;
+    }
+
+    /// Returns the whole slice this cursor was created over.
+    pub fn get_ref(&self) -> &'a [u8] {
+        self.data
+    }
+
+    /// Returns the part of the slice that hasn't been read yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.data.get(self.pos..).unwrap_or(&[])
+    }
+
+    /// Whether there's nothing left to read.
+    pub fn is_empty(&self) -> bool {
+        self.remaining().is_empty()
+    }
+
+    /// Reads `len` bytes, borrowed from the underlying slice.
+    pub fn read_bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.remaining().get(..len)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    #[inline(always)]
+    fn read_array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        let bytes = self.remaining().get(..N)?;
+        self.pos += N;
+        let mut buf = [0u8; N];
+        buf.copy_from_slice(bytes);
+        Some(buf)
+    }
+
+    /// Reads a UTF-8 encoded string with a given length (in bytes),
+    /// borrowed from the underlying slice.
+    ///
+    /// If the UTF-8 is invalid, the bytes are still consumed.
+    pub fn read_str_utf8(&mut self, len: usize) -> Option<Result<&'a str, str::Utf8Error>> {
+        self.read_bytes(len).map(str::from_utf8)
+    }
+
+    /// Reads a UTF-8 encoded string with a given length (in bytes),
+    /// borrowed from the underlying slice if it's valid.
+    ///
+    /// If any invalid UTF-8 is present, the bad chars are replaced with
+    /// U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    #[cfg(feature = "alloc")]
+    pub fn read_str_utf8_lossy(&mut self, len: usize) -> Option<Cow<'a, str>> {
+        self.read_bytes(len).map(String::from_utf8_lossy)
+    }
+
+    /// Reads bytes up to a null-terminator, borrowed from the underlying slice
+    /// (not including the terminator, which is skipped over).
+    ///
+    /// If `max` is provided, strings with more than `max` bytes before the terminator
+    /// return `None`.
+    pub fn read_cstr(&mut self, max: Option<usize>) -> Option<&'a [u8]> {
+        let rest = self.remaining();
+        let limit = max.map_or(rest.len(), |max| rest.len().min(max.saturating_add(1)));
+        let len = rest[..limit].iter().position(|&x| x == 0x00)?;
+        self.pos += len + 1;
+        Some(&rest[..len])
+    }
+
+    /// Reads a UTF-8 encoded, null-terminated string, borrowed from the underlying slice.
+    ///
+    /// If `max` is provided, strings with more than `max` bytes before the terminator
+    /// return `None`.
+    ///
+    /// If the UTF-8 is invalid, the bytes are still consumed.
+    pub fn read_cstr_utf8(
+        &mut self,
+        max: Option<usize>,
+    ) -> Option<Result<&'a str, str::Utf8Error>> {
+        self.read_cstr(max).map(str::from_utf8)
+    }
+
+    /// Reads a UTF-8 encoded, null-terminated string,
+    /// borrowed from the underlying slice if it's valid.
+    ///
+    /// If `max` is provided, strings with more than `max` bytes before the terminator
+    /// return `None`.
+    ///
+    /// If any invalid UTF-8 is present, the bad chars are replaced with
+    /// U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    #[cfg(feature = "alloc")]
+    pub fn read_cstr_utf8_lossy(&mut self, max: Option<usize>) -> Option<Cow<'a, str>> {
+        self.read_cstr(max).map(String::from_utf8_lossy)
+    }
+}
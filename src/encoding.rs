@@ -0,0 +1,215 @@
+use crate::{codepage, Error, Result};
+use alloc::{borrow::ToOwned, string::String, vec::Vec};
+use core::{convert::TryFrom, str};
+
+/// A text encoding, as a type, for reading and writing strings generically with methods such as
+/// [ReadStrings::read_str](trait.ReadStrings.html#method.read_str).
+///
+/// minio implements this for the encodings it has specific methods for, and it can be
+/// implemented for others (such as a format's own code page) to get the same set of readers
+/// and writers for free.
+pub trait Encoding {
+    /// How many bytes a code unit takes up, such as 2 for UTF-16.
+    ///
+    /// Lengths given to the generic readers and writers count these, and a null-terminator
+    /// is one code unit of zeroes. Defaults to 1.
+    const UNIT_WIDTH: usize = 1;
+
+    /// Decodes a string, which won't include the null-terminator if it had one.
+    /// `bytes` is always a whole number of code units.
+    ///
+    /// Invalid data should be reported with
+    /// [Error::InvalidEncoding](enum.Error.html#variant.InvalidEncoding)
+    /// (or whichever variant fits better), with the offset of the problem in `bytes`.
+    fn decode(bytes: &[u8]) -> Result<String>;
+
+    /// Decodes a string, replacing anything invalid with U+FFFD REPLACEMENT CHARACTER.
+    fn decode_lossy(bytes: &[u8]) -> String;
+
+    /// Encodes a string, without any null-terminator.
+    ///
+    /// Characters which can't be represented should be reported with
+    /// [Error::Unencodable](enum.Error.html#variant.Unencodable),
+    /// with the offset in bytes where they would have gone.
+    fn encode(s: &str) -> Result<Vec<u8>>;
+}
+
+/// UTF-8.
+///
+/// This has no values, it's only used as a type parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Utf8 {}
+
+impl Encoding for Utf8 {
+    fn decode(bytes: &[u8]) -> Result<String> {
+        Ok(str::from_utf8(bytes)?.to_owned())
+    }
+
+    fn decode_lossy(bytes: &[u8]) -> String {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+
+    fn encode(s: &str) -> Result<Vec<u8>> {
+        Ok(s.as_bytes().to_vec())
+    }
+}
+
+macro_rules! _utf16_encoding_impl {
+    ($name: ident, $doc: literal, $from: ident, $to: ident) => {
+        #[doc = $doc]
+        #[doc = ""]
+        #[doc = "This has no values, it's only used as a type parameter."]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {}
+
+        impl Encoding for $name {
+            const UNIT_WIDTH: usize = 2;
+
+            fn decode(bytes: &[u8]) -> Result<String> {
+                let units = bytes.chunks_exact(2).map(|x| u16::$from([x[0], x[1]]));
+                let s = crate::_string_from_utf16(&units.collect::<Vec<_>>())?;
+                match bytes.len() % 2 {
+                    0 => Ok(s),
+                    _ => Err(Error::InvalidUtf16 { string_offset: Some(bytes.len() as u64 - 1) }),
+                }
+            }
+
+            fn decode_lossy(bytes: &[u8]) -> String {
+                let units = bytes.chunks_exact(2).map(|x| u16::$from([x[0], x[1]]));
+                let mut s = char::decode_utf16(units)
+                    .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect::<String>();
+                if bytes.len() % 2 != 0 {
+                    s.push(char::REPLACEMENT_CHARACTER);
+                }
+                s
+            }
+
+            fn encode(s: &str) -> Result<Vec<u8>> {
+                Ok(s.encode_utf16().flat_map(u16::$to).collect())
+            }
+        }
+    };
+}
+
+_utf16_encoding_impl!(Utf16Le, "Little-endian UTF-16.", from_le_bytes, to_le_bytes);
+_utf16_encoding_impl!(Utf16Be, "Big-endian UTF-16.", from_be_bytes, to_be_bytes);
+
+/// Decodes one UTF-32 unit, which is invalid if it's cut short.
+fn _utf32_char(unit: &[u8], from_bytes: fn([u8; 4]) -> u32) -> Option<char> {
+    <[u8; 4]>::try_from(unit).ok().and_then(|x| char::from_u32(from_bytes(x)))
+}
+
+macro_rules! _utf32_encoding_impl {
+    ($name: ident, $doc: literal, $from: ident, $to: ident) => {
+        #[doc = $doc]
+        #[doc = ""]
+        #[doc = "This has no values, it's only used as a type parameter."]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {}
+
+        impl Encoding for $name {
+            const UNIT_WIDTH: usize = 4;
+
+            fn decode(bytes: &[u8]) -> Result<String> {
+                let mut s = String::with_capacity(bytes.len() / 4);
+                for (i, x) in bytes.chunks(4).enumerate() {
+                    match _utf32_char(x, u32::$from) {
+                        Some(c) => s.push(c),
+                        None => return Err(Error::InvalidUtf32 { string_offset: Some(i as u64 * 4) }),
+                    }
+                }
+                Ok(s)
+            }
+
+            fn decode_lossy(bytes: &[u8]) -> String {
+                bytes
+                    .chunks(4)
+                    .map(|x| _utf32_char(x, u32::$from).unwrap_or(char::REPLACEMENT_CHARACTER))
+                    .collect()
+            }
+
+            fn encode(s: &str) -> Result<Vec<u8>> {
+                Ok(s.chars().flat_map(|c| u32::$to(u32::from(c))).collect())
+            }
+        }
+    };
+}
+
+_utf32_encoding_impl!(Utf32Le, "Little-endian UTF-32.", from_le_bytes, to_le_bytes);
+_utf32_encoding_impl!(Utf32Be, "Big-endian UTF-32.", from_be_bytes, to_be_bytes);
+
+macro_rules! _single_byte_encoding_impl {
+    ($name: ident, $doc: literal, $decode: path, $encode: path) => {
+        #[doc = $doc]
+        #[doc = ""]
+        #[doc = "This has no values, it's only used as a type parameter."]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {}
+
+        impl Encoding for $name {
+            fn decode(bytes: &[u8]) -> Result<String> {
+                Ok(Self::decode_lossy(bytes))
+            }
+
+            fn decode_lossy(bytes: &[u8]) -> String {
+                bytes.iter().copied().map($decode).collect()
+            }
+
+            fn encode(s: &str) -> Result<Vec<u8>> {
+                let unencodable = |i, ch| Error::Unencodable { ch, string_offset: Some(i as u64) };
+                s.chars()
+                    .enumerate()
+                    .map(|(i, ch)| $encode(ch).ok_or_else(|| unencodable(i, ch)))
+                    .collect()
+            }
+        }
+    };
+}
+
+_single_byte_encoding_impl!(
+    Latin1, "Latin-1 (ISO 8859-1).",
+    codepage::latin1_decode, codepage::latin1_encode
+);
+_single_byte_encoding_impl!(
+    Cp1252, "Windows-1252.",
+    codepage::cp1252_decode, codepage::cp1252_encode
+);
+_single_byte_encoding_impl!(
+    Cp437, "Code page 437.",
+    codepage::cp437_decode, codepage::cp437_encode
+);
+
+#[cfg(feature = "cjk")]
+macro_rules! _multibyte_encoding_impl {
+    ($name: ident, $doc: literal, $enc: path) => {
+        #[doc = $doc]
+        #[doc = ""]
+        #[doc = "This has no values, it's only used as a type parameter."]
+        #[doc = ""]
+        #[doc = "Requires the `cjk` feature."]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {}
+
+        impl Encoding for $name {
+            fn decode(bytes: &[u8]) -> Result<String> {
+                crate::multibyte::decode($enc, bytes)
+            }
+
+            fn decode_lossy(bytes: &[u8]) -> String {
+                crate::multibyte::decode_lossy($enc, bytes)
+            }
+
+            fn encode(s: &str) -> Result<Vec<u8>> {
+                crate::multibyte::encode($enc, s)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "cjk")]
+_multibyte_encoding_impl!(ShiftJis, "Shift-JIS.", encoding_rs::SHIFT_JIS);
+#[cfg(feature = "cjk")]
+_multibyte_encoding_impl!(Gbk, "GBK.", encoding_rs::GBK);
+#[cfg(feature = "cjk")]
+_multibyte_encoding_impl!(EucKr, "EUC-KR.", encoding_rs::EUC_KR);
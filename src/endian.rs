@@ -0,0 +1,261 @@
+use crate::{io, ReadPrimitives};
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Primitive numbers which can be converted to and from their byte representation
+/// in any [ByteOrder](trait.ByteOrder.html).
+///
+/// This is implemented for every primitive number except `isize` and `usize`,
+/// and can't be implemented outside of minio.
+pub trait Primitive: private::Sealed + Copy {
+    /// The byte array this is represented as, such as `[u8; 4]` for `u32`.
+    type Bytes: AsRef<[u8]> + AsMut<[u8]> + Default;
+
+    /// Converts from little-endian bytes.
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+    /// Converts from big-endian bytes.
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+    /// Converts from native-endian bytes.
+    fn from_ne_bytes(bytes: Self::Bytes) -> Self;
+    /// Converts to little-endian bytes.
+    fn to_le_bytes(self) -> Self::Bytes;
+    /// Converts to big-endian bytes.
+    fn to_be_bytes(self) -> Self::Bytes;
+    /// Converts to native-endian bytes.
+    fn to_ne_bytes(self) -> Self::Bytes;
+}
+
+macro_rules! _primitive_impl {
+    ($($t: ty),*) => {$(
+        impl private::Sealed for $t {}
+
+        impl Primitive for $t {
+            type Bytes = [u8; core::mem::size_of::<$t>()];
+
+            #[inline(always)]
+            fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                <$t>::from_le_bytes(bytes)
+            }
+
+            #[inline(always)]
+            fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                <$t>::from_be_bytes(bytes)
+            }
+
+            #[inline(always)]
+            fn from_ne_bytes(bytes: Self::Bytes) -> Self {
+                <$t>::from_ne_bytes(bytes)
+            }
+
+            #[inline(always)]
+            fn to_le_bytes(self) -> Self::Bytes {
+                <$t>::to_le_bytes(self)
+            }
+
+            #[inline(always)]
+            fn to_be_bytes(self) -> Self::Bytes {
+                <$t>::to_be_bytes(self)
+            }
+
+            #[inline(always)]
+            fn to_ne_bytes(self) -> Self::Bytes {
+                <$t>::to_ne_bytes(self)
+            }
+        }
+    )*};
+}
+
+_primitive_impl!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128, f32, f64);
+
+/// A byte order, as a type, for writing code generic over endianness with methods such as
+/// [ReadPrimitives::read_u32](trait.ReadPrimitives.html#method.read_u32).
+///
+/// This is implemented by [LittleEndian](enum.LittleEndian.html),
+/// [BigEndian](enum.BigEndian.html) and [NativeEndian](enum.NativeEndian.html),
+/// and can't be implemented outside of minio.
+pub trait ByteOrder: private::Sealed {
+    /// Converts from bytes in this byte order.
+    fn from_bytes<T: Primitive>(bytes: T::Bytes) -> T;
+
+    /// Converts to bytes in this byte order.
+    fn to_bytes<T: Primitive>(val: T) -> T::Bytes;
+}
+
+macro_rules! _byte_order_impl {
+    ($name: ident, $doc: literal, $from: ident, $to: ident) => {
+        #[doc = $doc]
+        #[doc = ""]
+        #[doc = "This has no values, it's only used as a type parameter."]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+        pub enum $name {}
+
+        impl private::Sealed for $name {}
+
+        impl ByteOrder for $name {
+            #[inline(always)]
+            fn from_bytes<T: Primitive>(bytes: T::Bytes) -> T {
+                T::$from(bytes)
+            }
+
+            #[inline(always)]
+            fn to_bytes<T: Primitive>(val: T) -> T::Bytes {
+                val.$to()
+            }
+        }
+    };
+}
+
+_byte_order_impl!(LittleEndian, "Little-endian byte order.", from_le_bytes, to_le_bytes);
+_byte_order_impl!(BigEndian, "Big-endian byte order.", from_be_bytes, to_be_bytes);
+_byte_order_impl!(NativeEndian, "The target's native byte order.", from_ne_bytes, to_ne_bytes);
+
+/// A byte order chosen at runtime, for formats which declare theirs in a header
+/// (such as TIFF's `II`/`MM` or ELF's `EI_DATA`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Endian {
+    /// Little-endian byte order.
+    Little,
+    /// Big-endian byte order.
+    Big,
+}
+
+impl Endian {
+    /// The target's native byte order.
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Self = Endian::Little;
+
+    /// The target's native byte order.
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Self = Endian::Big;
+
+    /// Converts from bytes in this byte order.
+    #[inline(always)]
+    pub fn from_bytes<T: Primitive>(self, bytes: T::Bytes) -> T {
+        match self {
+            Endian::Little => LittleEndian::from_bytes(bytes),
+            Endian::Big => BigEndian::from_bytes(bytes),
+        }
+    }
+
+    /// Converts to bytes in this byte order.
+    #[inline(always)]
+    pub fn to_bytes<T: Primitive>(self, val: T) -> T::Bytes {
+        match self {
+            Endian::Little => LittleEndian::to_bytes(val),
+            Endian::Big => BigEndian::to_bytes(val),
+        }
+    }
+}
+
+macro_rules! _endian_reader_impl {
+    ($t: ty, $name: literal, $fn: ident, $with: ident) => {
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "in the stored byte order from the underlying reader."]
+        pub fn $fn(&mut self) -> io::Result<$t> {
+            self.inner.$with(self.endian)
+        }
+    };
+}
+
+/// Wraps a reader with a byte order chosen at runtime,
+/// so multi-byte numbers can be read without specifying it every time.
+///
+/// This implements `io::Read` (and `io::Seek` if the underlying reader does),
+/// so everything else in minio works on it as usual.
+#[derive(Clone, Debug)]
+pub struct EndianReader<R> {
+    inner: R,
+    endian: Endian,
+}
+
+#[rustfmt::skip]
+impl<R> EndianReader<R>
+where
+    R: io::Read,
+{
+    _endian_reader_impl!(i16, "an `i16`", read_i16, read_i16_with);
+    _endian_reader_impl!(u16, "a `u16`", read_u16, read_u16_with);
+    _endian_reader_impl!(i32, "an `i32`", read_i32, read_i32_with);
+    _endian_reader_impl!(u32, "a `u32`", read_u32, read_u32_with);
+    _endian_reader_impl!(i64, "an `i64`", read_i64, read_i64_with);
+    _endian_reader_impl!(u64, "a `u64`", read_u64, read_u64_with);
+    _endian_reader_impl!(i128, "an `i128`", read_i128, read_i128_with);
+    _endian_reader_impl!(u128, "a `u128`", read_u128, read_u128_with);
+    _endian_reader_impl!(f32, "an `f32`", read_f32, read_f32_with);
+    _endian_reader_impl!(f64, "an `f64`", read_f64, read_f64_with);
+}
+
+impl<R> EndianReader<R> {
+    /// Creates a new reader over `inner` which reads in the byte order `endian`.
+    pub fn new(inner: R, endian: Endian) -> Self {
+        Self { inner, endian }
+    }
+
+    /// Returns the byte order numbers are read in.
+    pub fn endian(&self) -> Endian {
+        self.endian
+    }
+
+    /// Changes the byte order numbers are read in.
+    pub fn set_endian(&mut self, endian: Endian) {
+        self.endian = endian;
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> io::Read for EndianReader<R>
+where
+    R: io::Read,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> io::Result<()> {
+        self.inner.read_exact(buf)
+    }
+}
+
+impl<R> io::BufRead for EndianReader<R>
+where
+    R: io::BufRead,
+{
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt)
+    }
+}
+
+impl<R> io::Seek for EndianReader<R>
+where
+    R: io::Seek,
+{
+    #[inline]
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
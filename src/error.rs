@@ -0,0 +1,208 @@
+use crate::io;
+#[cfg(feature = "alloc")]
+use alloc::string::{FromUtf16Error, FromUtf8Error};
+use core::{fmt, result, str::Utf8Error};
+
+/// A specialized `Result` type for minio's string readers.
+pub type Result<T> = result::Result<T, Error>;
+
+/// The error type for minio's string readers, which can fail on bad data
+/// as well as on I/O, and for the problems some string writers report through `io::Error`.
+///
+/// Offsets are in bytes, counted from where the failed read started (after any length prefix),
+/// so they point at the problem within the string rather than within the whole stream, which
+/// is why they're named `string_offset` (or `padding_offset`, for padding). For a stream offset,
+/// add the position the read started at, such as from `io::Seek::stream_position` or
+/// [Tracked::position](struct.Tracked.html#method.position). Running out of data always gives
+/// how many bytes of the string were read before that.
+///
+/// This converts to an `io::Error` (keeping the original for `Io`), so `?` still works in
+/// functions returning `io::Result`.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The underlying reader failed.
+    Io(io::Error),
+
+    /// The string wasn't valid UTF-8.
+    InvalidUtf8 {
+        /// Where the first invalid byte is, if known.
+        string_offset: Option<u64>,
+    },
+
+    /// The string wasn't valid UTF-16.
+    InvalidUtf16 {
+        /// Where the first invalid 16-bit integer is, if known.
+        string_offset: Option<u64>,
+    },
+
+    /// The string wasn't valid UTF-32.
+    InvalidUtf32 {
+        /// Where the first invalid 32-bit integer is, if known.
+        string_offset: Option<u64>,
+    },
+
+    /// The string wasn't valid in the (non-Unicode) encoding it was read as.
+    InvalidEncoding {
+        /// Where the first invalid byte sequence starts, if known.
+        string_offset: Option<u64>,
+    },
+
+    /// Padding which should have been all zeroes wasn't.
+    InvalidPadding {
+        /// Where the first non-zero byte is, counted from the start of the padding
+        /// (or of the whole field, for fixed-width strings), if known.
+        padding_offset: Option<u64>,
+    },
+
+    /// The string was longer than the maximum allowed.
+    LimitExceeded {
+        /// The maximum which was exceeded.
+        max: usize,
+        /// Where the limit was hit, if anything had been read yet.
+        string_offset: Option<u64>,
+    },
+
+    /// The reader ran out of data partway through.
+    UnexpectedEof {
+        /// How far into the string that happened, if known.
+        string_offset: Option<u64>,
+        /// The error the underlying reader gave, if it gave one rather than just running out.
+        source: Option<io::Error>,
+    },
+
+    /// A string being written had a character the encoding can't represent.
+    Unencodable {
+        /// The character.
+        ch: char,
+        /// Where it would have been written, if known.
+        string_offset: Option<u64>,
+    },
+}
+
+impl Error {
+    /// Returns the offset this error happened at, if it's known,
+    /// counted from the start of the string (or padding) being read.
+    pub fn offset(&self) -> Option<u64> {
+        match *self {
+            Error::Io(_) => None,
+            Error::InvalidUtf8 { string_offset }
+            | Error::InvalidUtf16 { string_offset }
+            | Error::InvalidUtf32 { string_offset }
+            | Error::InvalidEncoding { string_offset }
+            | Error::InvalidPadding { padding_offset: string_offset }
+            | Error::LimitExceeded { string_offset, .. }
+            | Error::UnexpectedEof { string_offset, .. }
+            | Error::Unencodable { string_offset, .. } => string_offset,
+        }
+    }
+
+    /// Returns the closest corresponding `io::ErrorKind`.
+    pub fn kind(&self) -> io::ErrorKind {
+        match self {
+            Error::Io(e) => e.kind(),
+            Error::InvalidUtf8 { .. } | Error::InvalidUtf16 { .. } => io::ErrorKind::InvalidData,
+            Error::InvalidUtf32 { .. } | Error::InvalidEncoding { .. } => {
+                io::ErrorKind::InvalidData
+            },
+            Error::InvalidPadding { .. } => io::ErrorKind::InvalidData,
+            Error::LimitExceeded { .. } => io::ErrorKind::InvalidData,
+            Error::UnexpectedEof { .. } => io::ErrorKind::UnexpectedEof,
+            Error::Unencodable { .. } => io::ErrorKind::InvalidInput,
+        }
+    }
+
+    fn description(&self) -> &'static str {
+        match self {
+            Error::Io(_) => "I/O error",
+            Error::InvalidUtf8 { .. } => "invalid UTF-8",
+            Error::InvalidUtf16 { .. } => "invalid UTF-16",
+            Error::InvalidUtf32 { .. } => "invalid UTF-32",
+            Error::InvalidEncoding { .. } => "invalid byte sequence",
+            Error::InvalidPadding { .. } => "non-zero padding",
+            Error::LimitExceeded { .. } => "string exceeds max length",
+            Error::UnexpectedEof { .. } => "unexpected end of file",
+            Error::Unencodable { .. } => "character can't be encoded",
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(e) => e.fmt(f),
+            Error::LimitExceeded { max, string_offset: Some(offset) } => {
+                write!(f, "{} of {} at offset {}", self.description(), max, offset)
+            },
+            Error::LimitExceeded { max, string_offset: None } => {
+                write!(f, "{} of {}", self.description(), max)
+            },
+            Error::Unencodable { ch, string_offset: Some(offset) } => {
+                write!(f, "character {:?} can't be encoded, at offset {}", ch, offset)
+            },
+            Error::Unencodable { ch, string_offset: None } => {
+                write!(f, "character {:?} can't be encoded", ch)
+            },
+            _ => match self.offset() {
+                Some(offset) => write!(f, "{} at offset {}", self.description(), offset),
+                None => f.write_str(self.description()),
+            },
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Error::Io(e) | Error::UnexpectedEof { source: Some(e), .. } => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// `io::ErrorKind::UnexpectedEof` becomes [UnexpectedEof](#variant.UnexpectedEof) (keeping the
+/// original as its `source`), everything else becomes [Io](#variant.Io).
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::UnexpectedEof => {
+                Error::UnexpectedEof { string_offset: None, source: Some(e) }
+            },
+            _ => Error::Io(e),
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(e: Error) -> Self {
+        match e {
+            Error::Io(e) | Error::UnexpectedEof { string_offset: None, source: Some(e) } => e,
+            #[cfg(feature = "std")]
+            e => io::Error::new(e.kind(), e),
+            #[cfg(not(feature = "std"))]
+            e => io::Error::new(e.kind(), e.description()),
+        }
+    }
+}
+
+impl From<Utf8Error> for Error {
+    fn from(e: Utf8Error) -> Self {
+        Error::InvalidUtf8 { string_offset: Some(e.valid_up_to() as u64) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<FromUtf8Error> for Error {
+    fn from(e: FromUtf8Error) -> Self {
+        e.utf8_error().into()
+    }
+}
+
+/// The offset is always `None`, as `FromUtf16Error` doesn't say where the problem was.
+/// minio's own UTF-16 readers don't go through this, so they do report it.
+#[cfg(feature = "alloc")]
+impl From<FromUtf16Error> for Error {
+    fn from(_: FromUtf16Error) -> Self {
+        Error::InvalidUtf16 { string_offset: None }
+    }
+}
@@ -0,0 +1,122 @@
+use crate::Primitive;
+use core::fmt;
+
+/// Integers which can hold the raw bits of a [Fixed](struct.Fixed.html) number.
+///
+/// This is implemented for every primitive integer, and can't be implemented outside of minio.
+pub trait FixedBits: Primitive + Ord + Default {
+    /// Converts to an `f64`, which is lossy for 64-bit and 128-bit integers.
+    fn to_f64(self) -> f64;
+
+    /// Converts from an `f64`, rounding half away from zero and saturating at the
+    /// integer's bounds. NaN becomes 0.
+    fn from_f64(val: f64) -> Self;
+}
+
+macro_rules! _fixed_bits_impl {
+    ($($t: ty),*) => {$(
+        impl FixedBits for $t {
+            #[inline(always)]
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            #[inline]
+            fn from_f64(val: f64) -> Self {
+                // `as` truncates towards zero and saturates, so only the rounding is left
+                let int = val as $t;
+                let frac = val - int as f64;
+                if frac >= 0.5 {
+                    int.saturating_add(1)
+                } else if frac <= -0.5 {
+                    int.saturating_sub(1)
+                } else {
+                    int
+                }
+            }
+        }
+    )*};
+}
+
+_fixed_bits_impl!(i8, u8, i16, u16, i32, u32, i64, u64, i128, u128);
+
+/// A fixed-point number with `FRAC` fractional bits, stored in the integer `I`
+/// (in other words, `I` divided by 2<sup>`FRAC`</sup>).
+///
+/// The raw bits are always kept as read, so reading and writing one back is lossless;
+/// converting to and from `f64` is only for convenience.
+/// The common Q formats have aliases, such as [Q16_16](type.Q16_16.html).
+///
+/// These are read and written with methods such as
+/// [ReadPrimitives::read_fixed_be](trait.ReadPrimitives.html#method.read_fixed_be).
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Fixed<I, const FRAC: u32>(I);
+
+/// Signed Q16.16, as in TrueType's `Fixed` and Nintendo DS formats.
+pub type Q16_16 = Fixed<i32, 16>;
+/// Signed Q8.8.
+pub type Q8_8 = Fixed<i16, 8>;
+/// Signed Q2.14, as in TrueType's `F2Dot14`.
+pub type Q2_14 = Fixed<i16, 14>;
+/// Signed Q20.12, as in PlayStation GTE and Nintendo DS 3D formats.
+pub type Q20_12 = Fixed<i32, 12>;
+
+impl<I, const FRAC: u32> Fixed<I, FRAC>
+where
+    I: FixedBits,
+{
+    /// 2<sup>`FRAC`</sup>, exactly.
+    const SCALE: f64 = f64::from_bits((1023 + FRAC as u64) << 52);
+
+    /// Creates a fixed-point number from its raw bits.
+    #[inline(always)]
+    pub fn from_bits(bits: I) -> Self {
+        Self(bits)
+    }
+
+    /// Returns the raw bits of this fixed-point number.
+    #[inline(always)]
+    pub fn to_bits(self) -> I {
+        self.0
+    }
+
+    /// Converts to an `f64`, which is exact unless `I` is wider than 32 bits.
+    #[inline]
+    pub fn to_f64(self) -> f64 {
+        self.0.to_f64() / Self::SCALE
+    }
+
+    /// Converts the nearest representable value to `val` (rounding half away from zero),
+    /// saturating at the bounds of `I`. NaN becomes 0.
+    #[inline]
+    pub fn from_f64(val: f64) -> Self {
+        Self(I::from_f64(val * Self::SCALE))
+    }
+}
+
+impl<I, const FRAC: u32> From<Fixed<I, FRAC>> for f64
+where
+    I: FixedBits,
+{
+    fn from(val: Fixed<I, FRAC>) -> Self {
+        val.to_f64()
+    }
+}
+
+impl<I, const FRAC: u32> fmt::Debug for Fixed<I, FRAC>
+where
+    I: FixedBits,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.to_f64(), f)
+    }
+}
+
+impl<I, const FRAC: u32> fmt::Display for Fixed<I, FRAC>
+where
+    I: FixedBits,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.to_f64(), f)
+    }
+}
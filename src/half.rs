@@ -0,0 +1,83 @@
+/// Converts the bits of an IEEE 754 binary16 ("half precision") float to an `f32`.
+///
+/// This is exact, including subnormals, infinities and NaN payloads.
+pub fn f16_to_f32(bits: u16) -> f32 {
+    let sign = u32::from(bits & 0x8000) << 16;
+    let exp = u32::from(bits >> 10) & 0x1F;
+    let man = u32::from(bits) & 0x3FF;
+    f32::from_bits(match (exp, man) {
+        (0, 0) => sign,
+        (0, _) => {
+            // subnormal, which is always normal as an f32
+            let shift = man.leading_zeros() - 21;
+            let exp = 113 - shift;
+            sign | (exp << 23) | ((man << shift) & 0x3FF) << 13
+        },
+        (0x1F, _) => sign | 0x7F80_0000 | (man << 13),
+        _ => sign | ((exp + 112) << 23) | (man << 13),
+    })
+}
+
+/// Converts an `f32` to the bits of an IEEE 754 binary16 ("half precision") float,
+/// rounding to nearest (ties to even).
+///
+/// Values too large become infinity, values too small become (signed) zero,
+/// and NaNs stay NaN.
+pub fn f32_to_f16(val: f32) -> u16 {
+    let bits = val.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exp = ((bits >> 23) & 0xFF) as i32;
+    let man = bits & 0x7F_FFFF;
+
+    if exp == 0xFF {
+        return if man == 0 { sign | 0x7C00 } else { sign | 0x7E00 | (man >> 13) as u16 };
+    }
+
+    // exponent rebiased for f16
+    let exp = exp - 127 + 15;
+    if exp >= 0x1F {
+        return sign | 0x7C00;
+    }
+
+    // the 10-bit result before rounding, and how many low bits of the 24-bit mantissa
+    // (with implicit bit) are being dropped
+    let (man, shift) = if exp > 0 {
+        (man, 13)
+    } else if exp >= -10 {
+        (man | 0x80_0000, (14 - exp) as u32)
+    } else {
+        return sign;
+    };
+    let rem = man & ((1 << shift) - 1);
+    let half = 1 << (shift - 1);
+    let mut out = (man >> shift) as u16;
+    if exp > 0 {
+        out |= (exp as u16) << 10;
+    }
+    if rem > half || (rem == half && out & 1 != 0) {
+        // can carry into the exponent, which is what should happen
+        out += 1;
+    }
+    sign | out
+}
+
+/// Converts the bits of a bfloat16 ("brain floating point") float to an `f32`.
+///
+/// This is exact, as bfloat16 is just the top half of an `f32`.
+pub fn bf16_to_f32(bits: u16) -> f32 {
+    f32::from_bits(u32::from(bits) << 16)
+}
+
+/// Converts an `f32` to the bits of a bfloat16 ("brain floating point") float,
+/// rounding to nearest (ties to even).
+///
+/// Values too large become infinity, and NaNs stay NaN.
+pub fn f32_to_bf16(val: f32) -> u16 {
+    let bits = val.to_bits();
+    if val.is_nan() {
+        // keep the top of the payload, but make sure it doesn't round into infinity
+        return ((bits >> 16) | 0x0040) as u16;
+    }
+    let round = 0x7FFF + ((bits >> 16) & 1);
+    (bits.wrapping_add(round) >> 16) as u16
+}
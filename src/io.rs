@@ -0,0 +1,334 @@
+//! A minimal stand-in for `std::io`, used when the `std` feature is disabled.
+//!
+//! Only what minio itself needs is here, with the same names and semantics as `std::io`,
+//! so implementing these traits for your own types is all it takes to use minio without `std`.
+
+use core::{fmt, result};
+
+/// A specialized `Result` type for I/O operations, as in `std::io`.
+pub type Result<T> = result::Result<T, Error>;
+
+/// A list specifying general categories of I/O error, as in `std::io`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Data not valid for the operation was encountered.
+    InvalidData,
+    /// A parameter was incorrect.
+    InvalidInput,
+    /// The operation was interrupted, and can typically be retried.
+    Interrupted,
+    /// A write returned `Ok(0)`.
+    WriteZero,
+    /// The end of the stream was reached before the operation could complete.
+    UnexpectedEof,
+    /// The operation isn't supported by this source or sink.
+    Unsupported,
+    /// Any other error.
+    Other,
+}
+
+impl ErrorKind {
+    fn as_str(self) -> &'static str {
+        match self {
+            ErrorKind::InvalidData => "invalid data",
+            ErrorKind::InvalidInput => "invalid input parameter",
+            ErrorKind::Interrupted => "operation interrupted",
+            ErrorKind::WriteZero => "write zero",
+            ErrorKind::UnexpectedEof => "unexpected end of file",
+            ErrorKind::Unsupported => "unsupported",
+            ErrorKind::Other => "other error",
+        }
+    }
+}
+
+/// The error type for I/O operations, as in `std::io`, but only able to carry a static message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Error {
+    kind: ErrorKind,
+    msg: Option<&'static str>,
+}
+
+impl Error {
+    /// Creates a new error from a known kind of error and a message.
+    pub fn new(kind: ErrorKind, msg: &'static str) -> Self {
+        Self { kind, msg: Some(msg) }
+    }
+
+    /// Returns the corresponding `ErrorKind` for this error.
+    pub fn kind(&self) -> ErrorKind {
+        self.kind
+    }
+}
+
+impl From<ErrorKind> for Error {
+    fn from(kind: ErrorKind) -> Self {
+        Self { kind, msg: None }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.msg.unwrap_or_else(|| self.kind.as_str()))
+    }
+}
+
+impl core::error::Error for Error {}
+
+/// Enumeration of possible methods to seek within an I/O object, as in `std::io`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SeekFrom {
+    /// Sets the offset to the provided number of bytes.
+    Start(u64),
+    /// Sets the offset to the size of this object plus the specified number of bytes.
+    End(i64),
+    /// Sets the offset to the current position plus the specified number of bytes.
+    Current(i64),
+}
+
+/// The `Read` trait allows for reading bytes from a source, as in `std::io`.
+pub trait Read {
+    /// Pull some bytes from this source into the specified buffer, returning how many bytes
+    /// were read. `Ok(0)` means the end of the source, or that `buf` was empty.
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Read the exact number of bytes required to fill `buf`.
+    fn read_exact(&mut self, mut buf: &mut [u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.read(buf) {
+                Ok(0) => return Err(ErrorKind::UnexpectedEof.into()),
+                Ok(n) => buf = &mut buf[n..],
+                Err(e) if e.kind() == ErrorKind::Interrupted => {},
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A trait for objects which are byte-oriented sinks, as in `std::io`.
+pub trait Write {
+    /// Write a buffer into this writer, returning how many bytes were written.
+    fn write(&mut self, buf: &[u8]) -> Result<usize>;
+
+    /// Flush this output stream, ensuring that all intermediately buffered contents reach
+    /// their destination.
+    fn flush(&mut self) -> Result<()>;
+
+    /// Attempts to write an entire buffer into this writer.
+    fn write_all(&mut self, mut buf: &[u8]) -> Result<()> {
+        while !buf.is_empty() {
+            match self.write(buf) {
+                Ok(0) => {
+                    return Err(Error::new(ErrorKind::WriteZero, "failed to write whole buffer"));
+                },
+                Ok(n) => buf = &buf[n..],
+                Err(e) if e.kind() == ErrorKind::Interrupted => {},
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A `BufRead` is a type of `Read`er which has an internal buffer, as in `std::io`.
+pub trait BufRead: Read {
+    /// Returns the contents of the internal buffer, filling it with more data from the inner
+    /// reader if it's empty. An empty buffer returned means the end of the source.
+    fn fill_buf(&mut self) -> Result<&[u8]>;
+
+    /// Tells this buffer that `amt` bytes have been consumed from the buffer,
+    /// so they should no longer be returned in calls to `read` or `fill_buf`.
+    fn consume(&mut self, amt: usize);
+}
+
+/// The `Seek` trait provides a cursor which can be moved within a stream of bytes,
+/// as in `std::io`.
+pub trait Seek {
+    /// Seek to an offset, in bytes, in a stream, returning the new position from the start.
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64>;
+
+    /// Returns the current seek position from the start of the stream.
+    fn stream_position(&mut self) -> Result<u64> {
+        self.seek(SeekFrom::Current(0))
+    }
+}
+
+impl<R: Read + ?Sized> Read for &mut R {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        (**self).read(buf)
+    }
+
+    #[inline]
+    fn read_exact(&mut self, buf: &mut [u8]) -> Result<()> {
+        (**self).read_exact(buf)
+    }
+}
+
+impl<W: Write + ?Sized> Write for &mut W {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        (**self).write(buf)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        (**self).flush()
+    }
+
+    #[inline]
+    fn write_all(&mut self, buf: &[u8]) -> Result<()> {
+        (**self).write_all(buf)
+    }
+}
+
+impl<B: BufRead + ?Sized> BufRead for &mut B {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        (**self).fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        (**self).consume(amt)
+    }
+}
+
+impl<S: Seek + ?Sized> Seek for &mut S {
+    #[inline]
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        (**self).seek(pos)
+    }
+}
+
+impl Read for &[u8] {
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let len = buf.len().min(self.len());
+        let (a, b) = self.split_at(len);
+        buf[..len].copy_from_slice(a);
+        *self = b;
+        Ok(len)
+    }
+}
+
+impl BufRead for &[u8] {
+    #[inline]
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        Ok(*self)
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        *self = &self[amt..];
+    }
+}
+
+impl Write for &mut [u8] {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        let len = buf.len().min(self.len());
+        let (a, b) = core::mem::take(self).split_at_mut(len);
+        a.copy_from_slice(&buf[..len]);
+        *self = b;
+        Ok(len)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Write for alloc::vec::Vec<u8> {
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    #[inline]
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// Wraps an in-memory buffer and gives it a `Seek` implementation, as in `std::io`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Cursor<T> {
+    inner: T,
+    pos: u64,
+}
+
+impl<T> Cursor<T> {
+    /// Creates a new cursor wrapping the provided buffer, starting at position 0.
+    pub fn new(inner: T) -> Self {
+        Self { inner, pos: 0 }
+    }
+
+    /// Consumes this cursor, returning the underlying buffer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Gets a reference to the underlying buffer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns the current position of this cursor.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Sets the position of this cursor.
+    pub fn set_position(&mut self, pos: u64) {
+        self.pos = pos;
+    }
+}
+
+impl<T: AsRef<[u8]>> Read for Cursor<T> {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        let data = self.inner.as_ref();
+        let start = (self.pos as usize).min(data.len());
+        let n = Read::read(&mut &data[start..], buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<T: AsRef<[u8]>> BufRead for Cursor<T> {
+    fn fill_buf(&mut self) -> Result<&[u8]> {
+        let data = self.inner.as_ref();
+        Ok(&data[(self.pos as usize).min(data.len())..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos += amt as u64;
+    }
+}
+
+impl<T: AsRef<[u8]>> Seek for Cursor<T> {
+    fn seek(&mut self, pos: SeekFrom) -> Result<u64> {
+        let (base, offset) = match pos {
+            SeekFrom::Start(n) => {
+                self.pos = n;
+                return Ok(n);
+            },
+            SeekFrom::End(n) => (self.inner.as_ref().len() as u64, n),
+            SeekFrom::Current(n) => (self.pos, n),
+        };
+        match base.checked_add_signed(offset) {
+            Some(n) => {
+                self.pos = n;
+                Ok(n)
+            },
+            None => Err(Error::new(
+                ErrorKind::InvalidInput,
+                "invalid seek to a negative or overflowing position",
+            )),
+        }
+    }
+}
@@ -1,7 +1,61 @@
-#[cfg(test)]
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(feature = "alloc")]
+extern crate alloc;
+
+#[cfg(all(test, feature = "std"))]
 mod tests;
 
-use std::{convert::identity, io, mem::size_of, slice};
+mod bits;
+mod codepage;
+mod cursor;
+#[cfg(feature = "alloc")]
+mod encoding;
+mod endian;
+mod error;
+mod fixed;
+mod half;
+#[cfg(feature = "cjk")]
+mod multibyte;
+#[cfg(feature = "alloc")]
+mod peek;
+mod tracked;
+
+#[cfg(feature = "std")]
+pub use std::io;
+
+#[cfg(not(feature = "std"))]
+pub mod io;
+
+pub use bits::{BitOrder, BitReader, BitWriter};
+pub use cursor::ByteCursor;
+#[cfg(feature = "alloc")]
+pub use encoding::{
+    Cp1252, Cp437, Encoding, Latin1, Utf16Be, Utf16Le, Utf32Be, Utf32Le, Utf8,
+};
+#[cfg(feature = "cjk")]
+pub use encoding::{EucKr, Gbk, ShiftJis};
+pub use endian::{
+    BigEndian, ByteOrder, Endian, EndianReader, LittleEndian, NativeEndian, Primitive,
+};
+pub use error::{Error, Result};
+pub use fixed::{Fixed, FixedBits, Q16_16, Q20_12, Q2_14, Q8_8};
+pub use half::{bf16_to_f32, f16_to_f32, f32_to_bf16, f32_to_f16};
+#[cfg(feature = "alloc")]
+pub use peek::Peekable;
+pub use tracked::Tracked;
+
+#[cfg(feature = "alloc")]
+use alloc::{
+    string::String,
+    vec,
+    vec::Vec,
+};
+use core::{
+    convert::{identity, TryFrom},
+    mem::{size_of, size_of_val},
+    slice,
+};
 
 macro_rules! _read_impl {
     // Used for i8 and u8, as they are endian independent.
@@ -13,27 +67,51 @@ macro_rules! _read_impl {
         }
     };
 
+    // Also used for i8 and u8, which don't get a generic version.
     ($t: ty, $name: literal, $le: ident, $be: ident, $ne: ident) => {
-        _read_impl!($t, identity, $t, $name, $le, $be, $ne);
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name] #[doc = "(little-endian) from the underlying reader."]
+        fn $le(&mut self) -> io::Result<$t> {
+            Ok(<$t>::from_le_bytes(_read_impl_body!(self, $t)))
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name] #[doc = "(big-endian) from the underlying reader."]
+        fn $be(&mut self) -> io::Result<$t> {
+            Ok(<$t>::from_be_bytes(_read_impl_body!(self, $t)))
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name] #[doc = "(native-endian) from the underlying reader."]
+        fn $ne(&mut self) -> io::Result<$t> {
+            Ok(<$t>::from_ne_bytes(_read_impl_body!(self, $t)))
+        }
     };
 
-    ($read_t: ty, $map: expr, $ret_t: ty, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+    ($t: ty, $name: literal, $generic: ident, $le: ident, $be: ident, $ne: ident) => {
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "in the byte order `E` from the underlying reader."]
+        fn $generic<E: ByteOrder>(&mut self) -> io::Result<$t> {
+            Ok(E::from_bytes(_read_impl_body!(self, $t)))
+        }
+
         #[inline(always)]
         #[doc = "Reads "] #[doc = $name] #[doc = "(little-endian) from the underlying reader."]
-        fn $le(&mut self) -> io::Result<$ret_t> {
-            Ok($map(<$read_t>::from_le_bytes(_read_impl_body!(self, $read_t))))
+        fn $le(&mut self) -> io::Result<$t> {
+            self.$generic::<LittleEndian>()
         }
 
         #[inline(always)]
         #[doc = "Reads "] #[doc = $name] #[doc = "(big-endian) from the underlying reader."]
-        fn $be(&mut self) -> io::Result<$ret_t> {
-            Ok($map(<$read_t>::from_be_bytes(_read_impl_body!(self, $read_t))))
+        fn $be(&mut self) -> io::Result<$t> {
+            self.$generic::<BigEndian>()
         }
 
         #[inline(always)]
         #[doc = "Reads "] #[doc = $name] #[doc = "(native-endian) from the underlying reader."]
-        fn $ne(&mut self) -> io::Result<$ret_t> {
-            Ok($map(<$read_t>::from_ne_bytes(_read_impl_body!(self, $read_t))))
+        fn $ne(&mut self) -> io::Result<$t> {
+            self.$generic::<NativeEndian>()
         }
     };
 }
@@ -56,285 +134,2336 @@ macro_rules! _write_impl {
         }
     };
 
+    // Also used for i8 and u8, which don't get a generic version.
     ($t: ty, $name: literal, $le: ident, $be: ident, $ne: ident) => {
-        _write_impl!($t, identity, $name, $le, $be, $ne);
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in little-endian format to the underlying writer."]
+        fn $le(&mut self, val: $t) -> io::Result<usize> {
+            self.write_all(&val.to_le_bytes()).map(|()| size_of::<$t>())
+        }
+
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in big-endian format to the underlying writer."]
+        fn $be(&mut self, val: $t) -> io::Result<usize> {
+            self.write_all(&val.to_be_bytes()).map(|()| size_of::<$t>())
+        }
+
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in native-endian format to the underlying writer."]
+        fn $ne(&mut self, val: $t) -> io::Result<usize> {
+            self.write_all(&val.to_ne_bytes()).map(|()| size_of::<$t>())
+        }
     };
 
-    ($t: ty, $map: expr, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+    ($t: ty, $name: literal, $generic: ident, $le: ident, $be: ident, $ne: ident) => {
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in the byte order `E` to the underlying writer."]
+        fn $generic<E: ByteOrder>(&mut self, val: $t) -> io::Result<usize> {
+            self.write_all(&E::to_bytes(val)).map(|()| size_of::<$t>())
+        }
+
         #[inline(always)]
         #[doc = "Writes "] #[doc = $name]
         #[doc = "in little-endian format to the underlying writer."]
         fn $le(&mut self, val: $t) -> io::Result<usize> {
-            self.write_all(&$map(val).to_le_bytes()).map(|()| size_of::<$t>())
+            self.$generic::<LittleEndian>(val)
         }
 
         #[inline(always)]
         #[doc = "Writes "] #[doc = $name]
         #[doc = "in big-endian format to the underlying writer."]
         fn $be(&mut self, val: $t) -> io::Result<usize> {
-            self.write_all(&$map(val).to_be_bytes()).map(|()| size_of::<$t>())
+            self.$generic::<BigEndian>(val)
         }
 
         #[inline(always)]
         #[doc = "Writes "] #[doc = $name]
         #[doc = "in native-endian format to the underlying writer."]
         fn $ne(&mut self, val: $t) -> io::Result<usize> {
-            self.write_all(&$map(val).to_ne_bytes()).map(|()| size_of::<$t>())
+            self.$generic::<NativeEndian>(val)
         }
     };
 }
 
-/// Provides methods for reading primitive numbers
-/// (except `isize` and `usize` as their size is platform dependent).
-#[rustfmt::skip]
-pub trait ReadPrimitives: io::Read {
-    _read_impl!(i8, "an `i8`", read_i8);
-    _read_impl!(u8, "a `u8`", read_u8);
-    _read_impl!(i8, "an `i8`", read_i8_le, read_i8_be, read_i8_ne);
-    _read_impl!(u8, "a `u8`", read_u8_le, read_u8_be, read_u8_ne);
-    _read_impl!(i16, "an `i16`", read_i16_le, read_i16_be, read_i16_ne);
-    _read_impl!(u16, "a `u16`", read_u16_le, read_u16_be, read_u16_ne);
-    _read_impl!(i32, "an `i32`", read_i32_le, read_i32_be, read_i32_ne);
-    _read_impl!(u32, "a `u32`", read_u32_le, read_u32_be, read_u32_ne);
-    _read_impl!(i64, "an `i64`", read_i64_le, read_i64_be, read_i64_ne);
-    _read_impl!(u64, "a `u64`", read_u64_le, read_u64_be, read_u64_ne);
-    _read_impl!(i128, "an `i128`", read_i128_le, read_i128_be, read_i128_ne);
-    _read_impl!(u128, "a `u128`", read_u128_le, read_u128_be, read_u128_ne);
-    _read_impl!(u32, |x| f32::from_bits(x), f32, "an `f32`", read_f32_le, read_f32_be, read_f32_ne);
-    _read_impl!(u64, |x| f64::from_bits(x), f64, "an `f64`", read_f64_le, read_f64_be, read_f64_ne);
+macro_rules! _read_with_impl {
+    ($t: ty, $name: literal, $fn: ident) => {
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "in the byte order `endian` from the underlying reader."]
+        fn $fn(&mut self, endian: Endian) -> io::Result<$t> {
+            Ok(endian.from_bytes(_read_impl_body!(self, $t)))
+        }
+    };
 }
 
-impl<R> ReadPrimitives for R where R: io::Read {}
+macro_rules! _write_with_impl {
+    ($t: ty, $name: literal, $fn: ident) => {
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in the byte order `endian` to the underlying writer."]
+        fn $fn(&mut self, endian: Endian, val: $t) -> io::Result<usize> {
+            self.write_all(&endian.to_bytes(val)).map(|()| size_of::<$t>())
+        }
+    };
+}
 
-/// Provides methods for writing primitive numbers
-/// (except `isize` and `usize` as their size is platform dependent).
-/// 
-/// All functions return bytes written, as all `io::Write` functions do.
-#[rustfmt::skip]
-pub trait WritePrimitives: io::Write {    
-    _write_impl!(i8, "an `i8`", write_i8);
-    _write_impl!(u8, "a `u8`", write_u8);
-    _write_impl!(i8, "an `i8`", write_i8_le, write_i8_be, write_i8_ne);
-    _write_impl!(u8, "a `u8`", write_u8_le, write_u8_be, write_u8_ne);
-    _write_impl!(i16, "an `i16`", write_i16_le, write_i16_be, write_i16_ne);
-    _write_impl!(u16, "a `u16`", write_u16_le, write_u16_be, write_u16_ne);
-    _write_impl!(i32, "an `i32`", write_i32_le, write_i32_be, write_i32_ne);
-    _write_impl!(u32, "a `u32`", write_u32_le, write_u32_be, write_u32_ne);
-    _write_impl!(i64, "an `i64`", write_i64_le, write_i64_be, write_i64_ne);
-    _write_impl!(u64, "a `u64`", write_u64_le, write_u64_be, write_u64_ne);
-    _write_impl!(i128, "an `i128`", write_i128_le, write_i128_be, write_i128_ne);
-    _write_impl!(u128, "a `u128`", write_u128_le, write_u128_be, write_u128_ne);
-    _write_impl!(f32, |x: f32| x.to_bits(), "an `f32`", write_f32_le, write_f32_be, write_f32_ne);
-    _write_impl!(f64, |x: f64| x.to_bits(), "an `f64`", write_f64_le, write_f64_be, write_f64_ne);
+macro_rules! _read_sized_impl {
+    (
+        $t: ty, $n: literal, $name: literal,
+        $via_le: ident, $via_be: ident, $le: ident, $be: ident, $ne: ident
+    ) => {
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name] #[doc = "(little-endian) from the underlying reader."]
+        fn $le(&mut self) -> io::Result<$t> {
+            self.$via_le($n).map(|x| x as $t)
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name] #[doc = "(big-endian) from the underlying reader."]
+        fn $be(&mut self) -> io::Result<$t> {
+            self.$via_be($n).map(|x| x as $t)
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name] #[doc = "(native-endian) from the underlying reader."]
+        fn $ne(&mut self) -> io::Result<$t> {
+            if cfg!(target_endian = "little") { self.$le() } else { self.$be() }
+        }
+    };
 }
 
-impl<W> WritePrimitives for W where W: io::Write {}
+macro_rules! _write_sized_impl {
+    (
+        $t: ty, $n: literal, $name: literal,
+        $via_le: ident, $via_be: ident, $le: ident, $be: ident, $ne: ident
+    ) => {
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in little-endian format to the underlying writer."]
+        #[doc = ""]
+        #[doc = "Values which don't fit are rejected with `io::ErrorKind::InvalidInput`."]
+        fn $le(&mut self, val: $t) -> io::Result<usize> {
+            self.$via_le(val.into(), $n)
+        }
+
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in big-endian format to the underlying writer."]
+        #[doc = ""]
+        #[doc = "Values which don't fit are rejected with `io::ErrorKind::InvalidInput`."]
+        fn $be(&mut self, val: $t) -> io::Result<usize> {
+            self.$via_be(val.into(), $n)
+        }
 
-fn _null_chunk_slow<R>(mut rdr: R, max: Option<usize>) -> io::Result<Vec<u8>>
+        #[inline(always)]
+        #[doc = "Writes "] #[doc = $name]
+        #[doc = "in native-endian format to the underlying writer."]
+        #[doc = ""]
+        #[doc = "Values which don't fit are rejected with `io::ErrorKind::InvalidInput`."]
+        fn $ne(&mut self, val: $t) -> io::Result<usize> {
+            if cfg!(target_endian = "little") { self.$le(val) } else { self.$be(val) }
+        }
+    };
+}
+
+macro_rules! _peek_seek_impl {
+    ($t: ty, $name: literal, $read: ident, $fn: ident) => {
+        #[inline]
+        #[doc = "Returns the next "] #[doc = $name]
+        #[doc = "without consuming it, by seeking back after reading it."]
+        #[doc = ""]
+        #[doc = "This seeks back even if the read fails, but if that seek fails too, its error"]
+        #[doc = "is returned and the position is wherever the reader left it."]
+        fn $fn(&mut self) -> io::Result<$t>
+        where
+            Self: io::Seek,
+        {
+            _peek(self, |rdr| rdr.$read())
+        }
+    };
+}
+
+fn _read_uleb128<R>(mut rdr: R, lenient: bool) -> io::Result<u64>
 where
     R: io::Read,
 {
-    let mut buf = Vec::new();
-    let mut count = 0;
+    let mut value = 0u64;
+    let mut shift = 0u32;
     loop {
-        if let Some(max) = max {
-            if count > max {
-                break Err(io::ErrorKind::UnexpectedEof.into());
+        let byte = rdr.read_u8()?;
+        if shift == 63 && byte > 0x01 {
+            break Err(io::Error::new(io::ErrorKind::InvalidData, "LEB128 value overflows u64"));
+        }
+        value |= u64::from(byte & 0x7F) << shift;
+        if byte & 0x80 == 0 {
+            // a trailing zero byte only adds nothing if something came before it
+            if !lenient && shift != 0 && byte == 0x00 {
+                break Err(io::Error::new(io::ErrorKind::InvalidData, "overlong LEB128 encoding"));
             }
+            break Ok(value);
         }
+        shift += 7;
+    }
+}
 
+fn _read_sleb128<R>(mut rdr: R, lenient: bool) -> io::Result<i64>
+where
+    R: io::Read,
+{
+    let mut value = 0i64;
+    let mut shift = 0u32;
+    let mut prev = None;
+    loop {
         let byte = rdr.read_u8()?;
-        if byte != 0x00 {
-            buf.push(byte);
-            count += 1;
-        } else {
-            break Ok(buf);
+        if shift == 63 && byte != 0x00 && byte != 0x7F {
+            break Err(io::Error::new(io::ErrorKind::InvalidData, "LEB128 value overflows i64"));
+        }
+        value |= i64::from(byte & 0x7F) << shift;
+        shift += 7;
+        if byte & 0x80 == 0 {
+            // a trailing sign byte is redundant if the byte before already had that sign
+            let redundant = match (prev, byte) {
+                (Some(prev), 0x00) => prev & 0x40 == 0,
+                (Some(prev), 0x7F) => prev & 0x40 != 0,
+                _ => false,
+            };
+            if !lenient && redundant {
+                break Err(io::Error::new(io::ErrorKind::InvalidData, "overlong LEB128 encoding"));
+            }
+            if shift < 64 && byte & 0x40 != 0 {
+                value |= -1 << shift;
+            }
+            break Ok(value);
         }
+        prev = Some(byte);
     }
 }
 
-fn _null_chunk<R>(mut rdr: R, max: Option<usize>) -> io::Result<Vec<u8>>
+fn _peek<R, T>(rdr: &mut R, read: impl FnOnce(&mut R) -> io::Result<T>) -> io::Result<T>
 where
-    R: io::Read + io::Seek,
+    R: io::Read + io::Seek + ?Sized,
 {
-    let mut length = 0usize;
-    while rdr.read_u8()? != 0 {
-        length += 1;
-        if let Some(max) = max {
-            if max >= length {
-                return Err(io::ErrorKind::UnexpectedEof.into());
+    let start = rdr.stream_position()?;
+    let result = read(rdr);
+    rdr.seek(io::SeekFrom::Start(start))?;
+    result
+}
+
+macro_rules! _read_into_impl {
+    // Used for i8 and u8, as they are endian independent.
+    ($t: ty, $name: literal, $fn: ident) => {
+        #[inline]
+        #[doc = "Reads enough "] #[doc = $name] #[doc = "to fill `dst` from the underlying reader."]
+        fn $fn(&mut self, dst: &mut [$t]) -> io::Result<()> {
+            self.read_exact(unsafe { _bytes_mut(dst) })
+        }
+    };
+
+    ($t: ty, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+        _read_into_impl!($t, identity, identity, $t, $name, $le, $be, $ne);
+    };
+
+    (
+        $bits: ty, $to_bits: expr, $from_bits: expr, $t: ty, $name: literal,
+        $le: ident, $be: ident, $ne: ident
+    ) => {
+        _read_into_impl!(
+            @order $bits, $to_bits, $from_bits, $t, $name, $le, from_le_bytes, "little-endian"
+        );
+        _read_into_impl!(
+            @order $bits, $to_bits, $from_bits, $t, $name, $be, from_be_bytes, "big-endian"
+        );
+        _read_into_impl!(
+            @order $bits, $to_bits, $from_bits, $t, $name, $ne, from_ne_bytes, "native-endian"
+        );
+    };
+
+    (
+        @order $bits: ty, $to_bits: expr, $from_bits: expr, $t: ty, $name: literal,
+        $fn: ident, $from_bytes: ident, $order: literal
+    ) => {
+        #[inline]
+        #[doc = "Reads enough "] #[doc = $name] #[doc = "("] #[doc = $order]
+        #[doc = ") to fill `dst` from the underlying reader."]
+        #[doc = ""]
+        #[doc = "This is a single `read_exact` followed by an in-place byte swap (if needed),"]
+        #[doc = "so it's much faster than reading one at a time."]
+        fn $fn(&mut self, dst: &mut [$t]) -> io::Result<()> {
+            self.read_exact(unsafe { _bytes_mut(dst) })?;
+            for x in dst.iter_mut() {
+                *x = $from_bits(<$bits>::$from_bytes($to_bits(*x).to_ne_bytes()));
+            }
+            Ok(())
+        }
+    };
+}
+
+macro_rules! _write_slice_impl {
+    // Used for i8 and u8, as they are endian independent.
+    ($t: ty, $name: literal, $fn: ident) => {
+        #[inline]
+        #[doc = "Writes every "] #[doc = $name] #[doc = "in `src` to the underlying writer."]
+        fn $fn(&mut self, src: &[$t]) -> io::Result<usize> {
+            let bytes = unsafe { slice::from_raw_parts(src.as_ptr() as *const u8, src.len()) };
+            self.write_all(bytes).map(|()| src.len())
+        }
+    };
+
+    ($t: ty, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+        _write_slice_impl!($t, identity, $name, $le, $be, $ne);
+    };
+
+    ($t: ty, $to_bits: expr, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+        _write_slice_impl!(@order $t, $to_bits, $name, $le, to_le_bytes, "little-endian");
+        _write_slice_impl!(@order $t, $to_bits, $name, $be, to_be_bytes, "big-endian");
+        _write_slice_impl!(@order $t, $to_bits, $name, $ne, to_ne_bytes, "native-endian");
+    };
+
+    (
+        @order $t: ty, $to_bits: expr, $name: literal,
+        $fn: ident, $to_bytes: ident, $order: literal
+    ) => {
+        #[inline]
+        #[doc = "Writes every "] #[doc = $name] #[doc = "in `src` in "] #[doc = $order]
+        #[doc = "format to the underlying writer."]
+        #[doc = ""]
+        #[doc = "Values are byte swapped (if needed) through a stack buffer in bulk,"]
+        #[doc = "so it's much faster than writing one at a time."]
+        fn $fn(&mut self, src: &[$t]) -> io::Result<usize> {
+            let mut buf = [0u8; _SLICE_BUF_LEN];
+            for chunk in src.chunks(_SLICE_BUF_LEN / size_of::<$t>()) {
+                let bytes = &mut buf[..size_of_val(chunk)];
+                for (dst, x) in bytes.chunks_exact_mut(size_of::<$t>()).zip(chunk) {
+                    dst.copy_from_slice(&$to_bits(*x).$to_bytes());
+                }
+                self.write_all(bytes)?;
             }
+            Ok(size_of_val(src))
+        }
+    };
+}
+
+macro_rules! _read_half_impl {
+    ($conv: ident, $name: literal, $generic: ident, $le: ident, $be: ident, $ne: ident) => {
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "in the byte order `E` from the underlying reader, widened to an `f32`."]
+        fn $generic<E: ByteOrder>(&mut self) -> io::Result<f32> {
+            Ok($conv(E::from_bytes(_read_impl_body!(self, u16))))
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "(little-endian) from the underlying reader, widened to an `f32`."]
+        fn $le(&mut self) -> io::Result<f32> {
+            self.$generic::<LittleEndian>()
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "(big-endian) from the underlying reader, widened to an `f32`."]
+        fn $be(&mut self) -> io::Result<f32> {
+            self.$generic::<BigEndian>()
+        }
+
+        #[inline(always)]
+        #[doc = "Reads "] #[doc = $name]
+        #[doc = "(native-endian) from the underlying reader, widened to an `f32`."]
+        fn $ne(&mut self) -> io::Result<f32> {
+            self.$generic::<NativeEndian>()
+        }
+    };
+
+    (@into $conv: ident, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+        _read_half_impl!(@into $conv, $name, $le, LittleEndian, "little-endian");
+        _read_half_impl!(@into $conv, $name, $be, BigEndian, "big-endian");
+        _read_half_impl!(@into $conv, $name, $ne, NativeEndian, "native-endian");
+    };
+
+    (@into $conv: ident, $name: literal, $fn: ident, $order: ty, $order_name: literal) => {
+        #[inline]
+        #[doc = "Reads enough "] #[doc = $name] #[doc = "("] #[doc = $order_name]
+        #[doc = ") to fill `dst` from the underlying reader, widened to `f32`s."]
+        fn $fn(&mut self, dst: &mut [f32]) -> io::Result<()> {
+            _read_half_into::<_, $order>(self, dst, $conv)
+        }
+    };
+}
+
+macro_rules! _write_half_impl {
+    ($conv: ident, $name: literal, $generic: ident, $le: ident, $be: ident, $ne: ident) => {
+        #[inline(always)]
+        #[doc = "Writes an `f32` as "] #[doc = $name]
+        #[doc = "in the byte order `E` to the underlying writer,"]
+        #[doc = "rounding to nearest (ties to even)."]
+        fn $generic<E: ByteOrder>(&mut self, val: f32) -> io::Result<usize> {
+            self.write_all(&E::to_bytes($conv(val))).map(|()| size_of::<u16>())
+        }
+
+        #[inline(always)]
+        #[doc = "Writes an `f32` as "] #[doc = $name]
+        #[doc = "in little-endian format to the underlying writer,"]
+        #[doc = "rounding to nearest (ties to even)."]
+        fn $le(&mut self, val: f32) -> io::Result<usize> {
+            self.$generic::<LittleEndian>(val)
+        }
+
+        #[inline(always)]
+        #[doc = "Writes an `f32` as "] #[doc = $name]
+        #[doc = "in big-endian format to the underlying writer,"]
+        #[doc = "rounding to nearest (ties to even)."]
+        fn $be(&mut self, val: f32) -> io::Result<usize> {
+            self.$generic::<BigEndian>(val)
+        }
+
+        #[inline(always)]
+        #[doc = "Writes an `f32` as "] #[doc = $name]
+        #[doc = "in native-endian format to the underlying writer,"]
+        #[doc = "rounding to nearest (ties to even)."]
+        fn $ne(&mut self, val: f32) -> io::Result<usize> {
+            self.$generic::<NativeEndian>(val)
+        }
+    };
+
+    (@slice $conv: ident, $name: literal, $le: ident, $be: ident, $ne: ident) => {
+        _write_half_impl!(@slice $conv, $name, $le, LittleEndian, "little-endian");
+        _write_half_impl!(@slice $conv, $name, $be, BigEndian, "big-endian");
+        _write_half_impl!(@slice $conv, $name, $ne, NativeEndian, "native-endian");
+    };
+
+    (@slice $conv: ident, $name: literal, $fn: ident, $order: ty, $order_name: literal) => {
+        #[inline]
+        #[doc = "Writes every `f32` in `src` as "] #[doc = $name] #[doc = "in"]
+        #[doc = $order_name] #[doc = "format to the underlying writer,"]
+        #[doc = "rounding to nearest (ties to even)."]
+        fn $fn(&mut self, src: &[f32]) -> io::Result<usize> {
+            _write_half_slice::<_, $order>(self, src, $conv)
+        }
+    };
+}
+
+fn _read_half_into<R, E>(mut rdr: R, dst: &mut [f32], conv: fn(u16) -> f32) -> io::Result<()>
+where
+    R: io::Read,
+    E: ByteOrder,
+{
+    let mut buf = [0u8; _SLICE_BUF_LEN];
+    for chunk in dst.chunks_mut(_SLICE_BUF_LEN / size_of::<u16>()) {
+        let bytes = &mut buf[..chunk.len() * size_of::<u16>()];
+        rdr.read_exact(bytes)?;
+        for (x, b) in chunk.iter_mut().zip(bytes.chunks_exact(size_of::<u16>())) {
+            *x = conv(E::from_bytes([b[0], b[1]]));
         }
     }
-    rdr.seek(io::SeekFrom::Current(-(length as i64 + 1)))?;
+    Ok(())
+}
 
-    let mut buf = vec![0u8; length];
-    rdr.read_exact(&mut buf[..])?;
-    rdr.seek(io::SeekFrom::Current(1))?; // move past null-term
-    Ok(buf)
+fn _write_half_slice<W, E>(mut wtr: W, src: &[f32], conv: fn(f32) -> u16) -> io::Result<usize>
+where
+    W: io::Write,
+    E: ByteOrder,
+{
+    let mut buf = [0u8; _SLICE_BUF_LEN];
+    for chunk in src.chunks(_SLICE_BUF_LEN / size_of::<u16>()) {
+        let bytes = &mut buf[..chunk.len() * size_of::<u16>()];
+        for (b, x) in bytes.chunks_exact_mut(size_of::<u16>()).zip(chunk) {
+            b.copy_from_slice(&E::to_bytes(conv(*x)));
+        }
+        wtr.write_all(bytes)?;
+    }
+    Ok(src.len() * size_of::<u16>())
 }
 
-/// Provides methods for reading strings of various encodings.
-pub trait ReadStrings: io::Read {
-    /// Reads a UTF-8 encoded string from the underlying reader with a given length (in bytes).
-    fn read_str_utf8(
-        &mut self,
-        len: usize,
-    ) -> io::Result<Result<String, std::string::FromUtf8Error>> {
-        Ok(String::from_utf8({
-            let mut buf = vec![0u8; len];
-            self.read_exact(&mut buf[..])?;
-            buf
-        }))
+fn _check_uint(val: u128, n: usize) -> io::Result<()> {
+    assert!(n > 0 && n <= 16, "can't write an integer 0 or over 16 bytes wide");
+    if n < 16 && val >> (8 * n) != 0 {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "value doesn't fit in bytes"));
     }
+    Ok(())
+}
 
-    /// Reads a UTF-8 encoded string from the underlying reader with a given length (in bytes).
-    ///
-    /// The validity of the UTF-8 is not checked, therefore this is marked **unsafe**.
-    unsafe fn read_str_utf8_unchecked(&mut self, len: usize) -> io::Result<String> {
-        Ok(String::from_utf8_unchecked({
-            let mut buf = vec![0u8; len];
-            self.read_exact(&mut buf[..])?;
-            buf
-        }))
+fn _check_int(val: i128, n: usize) -> io::Result<()> {
+    assert!(n > 0 && n <= 16, "can't write an integer 0 or over 16 bytes wide");
+    let shift = 128 - 8 * n as u32;
+    if (val << shift) >> shift != val {
+        return Err(io::Error::new(io::ErrorKind::InvalidInput, "value doesn't fit in bytes"));
     }
+    Ok(())
+}
 
-    /// Reads a UTF-8 encoded string from the underlying reader with a given length (in bytes).
+/// Size of the stack buffer bulk writes go through.
+const _SLICE_BUF_LEN: usize = 4096;
+
+/// Views a slice of primitive numbers as its underlying bytes.
+///
+/// # Safety
+/// `T` must be a primitive number (no padding, any bit pattern valid).
+#[inline(always)]
+unsafe fn _bytes_mut<T>(s: &mut [T]) -> &mut [u8] {
+    slice::from_raw_parts_mut(s.as_mut_ptr() as *mut u8, size_of_val(s))
+}
+
+/// Maps a signed integer onto an unsigned one so that small magnitudes stay small
+/// (`0, -1, 1, -2, ...` becomes `0, 1, 2, 3, ...`), as protobuf's `sint64` does.
+#[inline]
+pub fn zigzag_encode(val: i64) -> u64 {
+    ((val << 1) ^ (val >> 63)) as u64
+}
+
+/// Reverses [zigzag_encode](fn.zigzag_encode.html).
+#[inline]
+pub fn zigzag_decode(val: u64) -> i64 {
+    (val >> 1) as i64 ^ -((val & 1) as i64)
+}
+
+/// Provides methods for reading primitive numbers
+/// (except `isize` and `usize` as their size is platform dependent).
+#[rustfmt::skip]
+pub trait ReadPrimitives: io::Read {
+    _read_impl!(i8, "an `i8`", read_i8);
+    _read_impl!(u8, "a `u8`", read_u8);
+    _read_impl!(i8, "an `i8`", read_i8_le, read_i8_be, read_i8_ne);
+    _read_impl!(u8, "a `u8`", read_u8_le, read_u8_be, read_u8_ne);
+    _read_impl!(i16, "an `i16`", read_i16, read_i16_le, read_i16_be, read_i16_ne);
+    _read_impl!(u16, "a `u16`", read_u16, read_u16_le, read_u16_be, read_u16_ne);
+    _read_impl!(i32, "an `i32`", read_i32, read_i32_le, read_i32_be, read_i32_ne);
+    _read_impl!(u32, "a `u32`", read_u32, read_u32_le, read_u32_be, read_u32_ne);
+    _read_impl!(i64, "an `i64`", read_i64, read_i64_le, read_i64_be, read_i64_ne);
+    _read_impl!(u64, "a `u64`", read_u64, read_u64_le, read_u64_be, read_u64_ne);
+    _read_impl!(i128, "an `i128`", read_i128, read_i128_le, read_i128_be, read_i128_ne);
+    _read_impl!(u128, "a `u128`", read_u128, read_u128_le, read_u128_be, read_u128_ne);
+    _read_impl!(f32, "an `f32`", read_f32, read_f32_le, read_f32_be, read_f32_ne);
+    _read_impl!(f64, "an `f64`", read_f64, read_f64_le, read_f64_be, read_f64_ne);
+
+    _read_with_impl!(i16, "an `i16`", read_i16_with);
+    _read_with_impl!(u16, "a `u16`", read_u16_with);
+    _read_with_impl!(i32, "an `i32`", read_i32_with);
+    _read_with_impl!(u32, "a `u32`", read_u32_with);
+    _read_with_impl!(i64, "an `i64`", read_i64_with);
+    _read_with_impl!(u64, "a `u64`", read_u64_with);
+    _read_with_impl!(i128, "an `i128`", read_i128_with);
+    _read_with_impl!(u128, "a `u128`", read_u128_with);
+    _read_with_impl!(f32, "an `f32`", read_f32_with);
+    _read_with_impl!(f64, "an `f64`", read_f64_with);
+
+    _read_sized_impl!(
+        u32, 3, "a 24-bit unsigned integer", read_uint_le, read_uint_be,
+        read_u24_le, read_u24_be, read_u24_ne
+    );
+    _read_sized_impl!(
+        i32, 3, "a 24-bit signed integer", read_int_le, read_int_be,
+        read_i24_le, read_i24_be, read_i24_ne
+    );
+    _read_sized_impl!(
+        u64, 6, "a 48-bit unsigned integer", read_uint_le, read_uint_be,
+        read_u48_le, read_u48_be, read_u48_ne
+    );
+    _read_sized_impl!(
+        i64, 6, "a 48-bit signed integer", read_int_le, read_int_be,
+        read_i48_le, read_i48_be, read_i48_ne
+    );
+
+    /// Reads an unsigned integer `n` bytes wide (little-endian) from the underlying reader.
     ///
-    /// If any invalid UTF-8 is present, the bad chars are replaced with
-    /// U+FFFD REPLACEMENT CHARACTER, which looks like this: �
-    fn read_str_utf8_lossy(&mut self, len: usize) -> io::Result<String> {
-        let mut buf = vec![0u8; len];
-        self.read_exact(&mut buf[..])?;
-        Ok(String::from_utf8_lossy(&buf).into_owned())
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 16.
+    fn read_uint_le(&mut self, n: usize) -> io::Result<u128> {
+        assert!(n > 0 && n <= 16, "can't read an integer 0 or over 16 bytes wide");
+        let mut buf = [0u8; 16];
+        self.read_exact(&mut buf[..n])?;
+        Ok(u128::from_le_bytes(buf))
     }
 
-    /// Reads a UTF-16 encoded string from the underlying reader with a given length
-    /// (in 16-bit integers, **NOT** bytes).
+    /// Reads an unsigned integer `n` bytes wide (big-endian) from the underlying reader.
     ///
     /// # Panics
-    /// Panics if `len * 2` overflows usize.
-    #[inline(always)]
-    fn read_str_utf16(
-        &mut self,
-        len: usize,
-    ) -> io::Result<Result<String, std::string::FromUtf16Error>> {
-        let mut buf = vec![0u8; len.checked_mul(2).expect("input length overflows usize")];
-        self.read_exact(&mut buf[..])?;
-        Ok(String::from_utf16(unsafe {
-            slice::from_raw_parts(buf.as_ptr() as *const _, len)
-        }))
+    /// Panics if `n` is 0 or greater than 16.
+    fn read_uint_be(&mut self, n: usize) -> io::Result<u128> {
+        assert!(n > 0 && n <= 16, "can't read an integer 0 or over 16 bytes wide");
+        let mut buf = [0u8; 16];
+        self.read_exact(&mut buf[16 - n..])?;
+        Ok(u128::from_be_bytes(buf))
     }
 
-    /// Reads a UTF-16 encoded string from the underlying reader with a given length
-    /// (in 16-bit integers, **NOT** bytes).
-    ///
-    /// If any invalid UTF-16 is present, the bad chars are replaced
-    /// with U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    /// Reads a two's complement signed integer `n` bytes wide (little-endian)
+    /// from the underlying reader.
     ///
     /// # Panics
-    /// Panics if `len * 2` overflows usize.
-    #[inline(always)]
-    fn read_str_utf16_lossy(&mut self, len: usize) -> io::Result<String> {
-        let mut buf = vec![0u8; len.checked_mul(2).expect("input length overflows usize")];
-        self.read_exact(&mut buf[..])?;
-        Ok(String::from_utf16_lossy(unsafe {
-            slice::from_raw_parts(buf.as_ptr() as *const _, len)
-        }))
+    /// Panics if `n` is 0 or greater than 16.
+    fn read_int_le(&mut self, n: usize) -> io::Result<i128> {
+        let x = self.read_uint_le(n)?;
+        let shift = 128 - 8 * n as u32;
+        Ok(((x << shift) as i128) >> shift)
     }
 
-    /// **If your reader has `io::Seek`, use
-    /// [read_cstr_utf8_fast](#method.read_cstr_utf8_fast)
-    /// instead.**
-    ///
-    /// Reads a UTF-8 encoded, null-terminated string from the underlying reader.
+    /// Reads a two's complement signed integer `n` bytes wide (big-endian)
+    /// from the underlying reader.
     ///
-    /// If `max` is provided, it'll only try to read that many bytes before erroring (giving up).
-    fn read_cstr_utf8(
-        &mut self,
-        max: Option<usize>,
-    ) -> io::Result<Result<String, std::string::FromUtf8Error>> {
-        _null_chunk_slow(self, max).map(|buf| String::from_utf8(buf))
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 16.
+    fn read_int_be(&mut self, n: usize) -> io::Result<i128> {
+        let x = self.read_uint_be(n)?;
+        let shift = 128 - 8 * n as u32;
+        Ok(((x << shift) as i128) >> shift)
     }
 
-    /// **If your reader has `io::Seek`, use
-    /// [read_cstr_utf8_unchecked_fast](#method.read_cstr_utf8_unchecked_fast)
-    /// instead.**
-    ///
-    /// Reads a UTF-8 encoded, null-terminated string from the underlying reader.
-    ///
-    /// If `max` is provided, it'll only try to read that many bytes before erroring (giving up).
+    _read_half_impl!(
+        f16_to_f32, "an IEEE binary16 float",
+        read_f16, read_f16_le, read_f16_be, read_f16_ne
+    );
+    _read_half_impl!(
+        bf16_to_f32, "a bfloat16 float",
+        read_bf16, read_bf16_le, read_bf16_be, read_bf16_ne
+    );
+
+    _read_into_impl!(i8, "`i8`s", read_i8_into);
+    _read_into_impl!(u8, "`u8`s", read_u8_into);
+    _read_into_impl!(i16, "`i16`s", read_i16_into_le, read_i16_into_be, read_i16_into_ne);
+    _read_into_impl!(u16, "`u16`s", read_u16_into_le, read_u16_into_be, read_u16_into_ne);
+    _read_into_impl!(i32, "`i32`s", read_i32_into_le, read_i32_into_be, read_i32_into_ne);
+    _read_into_impl!(u32, "`u32`s", read_u32_into_le, read_u32_into_be, read_u32_into_ne);
+    _read_into_impl!(i64, "`i64`s", read_i64_into_le, read_i64_into_be, read_i64_into_ne);
+    _read_into_impl!(u64, "`u64`s", read_u64_into_le, read_u64_into_be, read_u64_into_ne);
+    _read_into_impl!(i128, "`i128`s", read_i128_into_le, read_i128_into_be, read_i128_into_ne);
+    _read_into_impl!(u128, "`u128`s", read_u128_into_le, read_u128_into_be, read_u128_into_ne);
+    _read_into_impl!(
+        u32, f32::to_bits, f32::from_bits, f32, "`f32`s",
+        read_f32_into_le, read_f32_into_be, read_f32_into_ne
+    );
+    _read_into_impl!(
+        u64, f64::to_bits, f64::from_bits, f64, "`f64`s",
+        read_f64_into_le, read_f64_into_be, read_f64_into_ne
+    );
+    _read_half_impl!(
+        @into f16_to_f32, "IEEE binary16 floats",
+        read_f16_into_le, read_f16_into_be, read_f16_into_ne
+    );
+    _read_half_impl!(
+        @into bf16_to_f32, "bfloat16 floats",
+        read_bf16_into_le, read_bf16_into_be, read_bf16_into_ne
+    );
+
+    /// Reads a fixed-point number with `FRAC` fractional bits, stored as an `I`
+    /// in the byte order `E`, from the underlying reader.
+    #[inline(always)]
+    fn read_fixed<E, I, const FRAC: u32>(&mut self) -> io::Result<Fixed<I, FRAC>>
+    where
+        E: ByteOrder,
+        I: FixedBits,
+    {
+        let mut bytes = I::Bytes::default();
+        self.read_exact(bytes.as_mut())?;
+        Ok(Fixed::from_bits(E::from_bytes(bytes)))
+    }
+
+    /// Reads a fixed-point number with `FRAC` fractional bits, stored as an `I`
+    /// (little-endian), from the underlying reader.
+    #[inline(always)]
+    fn read_fixed_le<I: FixedBits, const FRAC: u32>(&mut self) -> io::Result<Fixed<I, FRAC>> {
+        self.read_fixed::<LittleEndian, I, FRAC>()
+    }
+
+    /// Reads a fixed-point number with `FRAC` fractional bits, stored as an `I`
+    /// (big-endian), from the underlying reader.
+    #[inline(always)]
+    fn read_fixed_be<I: FixedBits, const FRAC: u32>(&mut self) -> io::Result<Fixed<I, FRAC>> {
+        self.read_fixed::<BigEndian, I, FRAC>()
+    }
+
+    /// Reads a fixed-point number with `FRAC` fractional bits, stored as an `I`
+    /// (native-endian), from the underlying reader.
+    #[inline(always)]
+    fn read_fixed_ne<I: FixedBits, const FRAC: u32>(&mut self) -> io::Result<Fixed<I, FRAC>> {
+        self.read_fixed::<NativeEndian, I, FRAC>()
+    }
+
+    /// Reads an unsigned LEB128 encoded `u64` from the underlying reader.
+    ///
+    /// Overlong encodings (padded with redundant `0x80` bytes) and ones overflowing a `u64` are
+    /// rejected with `io::ErrorKind::InvalidData`. DWARF and WebAssembly allow the padding,
+    /// so [read_uleb128_u64_lenient](#method.read_uleb128_u64_lenient) accepts it.
+    fn read_uleb128_u64(&mut self) -> io::Result<u64> {
+        _read_uleb128(self, false)
+    }
+
+    /// Reads an unsigned LEB128 encoded `u64` from the underlying reader,
+    /// accepting encodings padded with redundant `0x80` bytes.
+    ///
+    /// Anything over 10 bytes or overflowing a `u64` is still rejected with
+    /// `io::ErrorKind::InvalidData`.
+    fn read_uleb128_u64_lenient(&mut self) -> io::Result<u64> {
+        _read_uleb128(self, true)
+    }
+
+    /// Reads a signed LEB128 encoded `i64` from the underlying reader.
+    ///
+    /// Overlong encodings (padded with redundant sign bytes) and ones overflowing an `i64` are
+    /// rejected with `io::ErrorKind::InvalidData`. DWARF and WebAssembly allow the padding,
+    /// so [read_sleb128_i64_lenient](#method.read_sleb128_i64_lenient) accepts it.
+    fn read_sleb128_i64(&mut self) -> io::Result<i64> {
+        _read_sleb128(self, false)
+    }
+
+    /// Reads a signed LEB128 encoded `i64` from the underlying reader,
+    /// accepting encodings padded with redundant sign bytes.
+    ///
+    /// Anything over 10 bytes or overflowing an `i64` is still rejected with
+    /// `io::ErrorKind::InvalidData`.
+    fn read_sleb128_i64_lenient(&mut self) -> io::Result<i64> {
+        _read_sleb128(self, true)
+    }
+
+    /// Reads a zigzag encoded `i64` (stored as an unsigned LEB128) from the underlying reader,
+    /// as used by protobuf's `sint64`.
+    ///
+    /// See [read_uleb128_u64](#method.read_uleb128_u64) for what's rejected.
+    fn read_zigzag_i64(&mut self) -> io::Result<i64> {
+        self.read_uleb128_u64().map(zigzag_decode)
+    }
+
+    _peek_seek_impl!(u8, "`u8`", read_u8, peek_u8);
+    _peek_seek_impl!(u16, "`u16` (little-endian)", read_u16_le, peek_u16_le);
+    _peek_seek_impl!(u16, "`u16` (big-endian)", read_u16_be, peek_u16_be);
+    _peek_seek_impl!(u32, "`u32` (little-endian)", read_u32_le, peek_u32_le);
+    _peek_seek_impl!(u32, "`u32` (big-endian)", read_u32_be, peek_u32_be);
+    _peek_seek_impl!(u64, "`u64` (little-endian)", read_u64_le, peek_u64_le);
+    _peek_seek_impl!(u64, "`u64` (big-endian)", read_u64_be, peek_u64_be);
+
+    /// Fills `buf` with the next bytes without consuming them,
+    /// by seeking back after reading them.
+    ///
+    /// This seeks back even if the read fails, but if that seek fails too, its error
+    /// is returned and the position is wherever the reader left it.
+    #[inline]
+    fn peek_exact(&mut self, buf: &mut [u8]) -> io::Result<()>
+    where
+        Self: io::Seek,
+    {
+        _peek(self, |rdr| rdr.read_exact(buf))
+    }
+
+    /// Reads and discards `n` bytes, a few KiB at a time.
+    ///
+    /// If the reader runs out first, this fails with `io::ErrorKind::UnexpectedEof`.
+    ///
+    /// Readers with `io::Seek` can use [skip_fast](#method.skip_fast) instead. This can't seek
+    /// by itself when that's possible, as this trait is implemented for every reader and
+    /// picking a different body for seekable ones would need specialization,
+    /// which stable Rust doesn't have.
+    fn skip(&mut self, mut n: u64) -> io::Result<()> {
+        let mut buf = [0u8; _SLICE_BUF_LEN];
+        while n > 0 {
+            let len = buf.len().min(usize::try_from(n).unwrap_or(usize::MAX));
+            match self.read(&mut buf[..len]) {
+                Ok(0) => return Err(io::ErrorKind::UnexpectedEof.into()),
+                Ok(read) => n -= read as u64,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Skips `n` bytes by seeking past them.
+    ///
+    /// *Unlike [skip](#method.skip), this doesn't notice if that goes past the end,
+    /// as seeking there is allowed. The next read will fail instead.*
+    fn skip_fast(&mut self, n: u64) -> io::Result<()>
+    where
+        Self: io::Seek,
+    {
+        let n = i64::try_from(n)
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "skip length overflows i64"))?;
+        self.seek(io::SeekFrom::Current(n)).map(|_| ())
+    }
+
+    /// Skips ahead to the next stream position that's a multiple of `align` by seeking,
+    /// returning how many bytes were skipped.
+    ///
+    /// *Like [skip_fast](#method.skip_fast), this doesn't notice if that goes past the end.
+    /// Readers which aren't `io::Seek` can be wrapped in a [Tracked](struct.Tracked.html),
+    /// which has its own `align_to`.*
+    ///
+    /// # Panics
+    /// Panics if `align` is 0.
+    fn align_to(&mut self, align: usize) -> io::Result<usize>
+    where
+        Self: io::Seek,
+    {
+        let pad = _padding_len(self.stream_position()?, align);
+        self.skip_fast(pad as u64).map(|()| pad)
+    }
+
+    /// Reads `n` bytes of padding, failing with `io::ErrorKind::InvalidData` wrapping an
+    /// [Error::InvalidPadding](enum.Error.html#variant.InvalidPadding)
+    /// (where `std` is enabled) if any of them aren't zero.
+    fn expect_zero_padding(&mut self, n: usize) -> io::Result<()> {
+        let mut buf = [0u8; _SLICE_BUF_LEN];
+        let mut offset = 0;
+        while offset < n {
+            let len = buf.len().min(n - offset);
+            self.read_exact(&mut buf[..len])?;
+            if let Some(i) = buf[..len].iter().position(|&x| x != 0x00) {
+                return Err(Error::InvalidPadding { padding_offset: Some((offset + i) as u64) }.into());
+            }
+            offset += len;
+        }
+        Ok(())
+    }
+}
+
+impl<R> ReadPrimitives for R where R: io::Read {}
+
+/// Provides methods for writing primitive numbers
+/// (except `isize` and `usize` as their size is platform dependent).
+/// 
+/// All functions return bytes written, as all `io::Write` functions do.
+#[rustfmt::skip]
+pub trait WritePrimitives: io::Write {    
+    _write_impl!(i8, "an `i8`", write_i8);
+    _write_impl!(u8, "a `u8`", write_u8);
+    _write_impl!(i8, "an `i8`", write_i8_le, write_i8_be, write_i8_ne);
+    _write_impl!(u8, "a `u8`", write_u8_le, write_u8_be, write_u8_ne);
+    _write_impl!(i16, "an `i16`", write_i16, write_i16_le, write_i16_be, write_i16_ne);
+    _write_impl!(u16, "a `u16`", write_u16, write_u16_le, write_u16_be, write_u16_ne);
+    _write_impl!(i32, "an `i32`", write_i32, write_i32_le, write_i32_be, write_i32_ne);
+    _write_impl!(u32, "a `u32`", write_u32, write_u32_le, write_u32_be, write_u32_ne);
+    _write_impl!(i64, "an `i64`", write_i64, write_i64_le, write_i64_be, write_i64_ne);
+    _write_impl!(u64, "a `u64`", write_u64, write_u64_le, write_u64_be, write_u64_ne);
+    _write_impl!(i128, "an `i128`", write_i128, write_i128_le, write_i128_be, write_i128_ne);
+    _write_impl!(u128, "a `u128`", write_u128, write_u128_le, write_u128_be, write_u128_ne);
+    _write_impl!(f32, "an `f32`", write_f32, write_f32_le, write_f32_be, write_f32_ne);
+    _write_impl!(f64, "an `f64`", write_f64, write_f64_le, write_f64_be, write_f64_ne);
+
+    _write_with_impl!(i16, "an `i16`", write_i16_with);
+    _write_with_impl!(u16, "a `u16`", write_u16_with);
+    _write_with_impl!(i32, "an `i32`", write_i32_with);
+    _write_with_impl!(u32, "a `u32`", write_u32_with);
+    _write_with_impl!(i64, "an `i64`", write_i64_with);
+    _write_with_impl!(u64, "a `u64`", write_u64_with);
+    _write_with_impl!(i128, "an `i128`", write_i128_with);
+    _write_with_impl!(u128, "a `u128`", write_u128_with);
+    _write_with_impl!(f32, "an `f32`", write_f32_with);
+    _write_with_impl!(f64, "an `f64`", write_f64_with);
+
+    _write_sized_impl!(
+        u32, 3, "a 24-bit unsigned integer", write_uint_le, write_uint_be,
+        write_u24_le, write_u24_be, write_u24_ne
+    );
+    _write_sized_impl!(
+        i32, 3, "a 24-bit signed integer", write_int_le, write_int_be,
+        write_i24_le, write_i24_be, write_i24_ne
+    );
+    _write_sized_impl!(
+        u64, 6, "a 48-bit unsigned integer", write_uint_le, write_uint_be,
+        write_u48_le, write_u48_be, write_u48_ne
+    );
+    _write_sized_impl!(
+        i64, 6, "a 48-bit signed integer", write_int_le, write_int_be,
+        write_i48_le, write_i48_be, write_i48_ne
+    );
+
+    /// Writes an unsigned integer `n` bytes wide in little-endian format to the underlying writer.
+    ///
+    /// Values which don't fit in `n` bytes are rejected with `io::ErrorKind::InvalidInput`.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 16.
+    fn write_uint_le(&mut self, val: u128, n: usize) -> io::Result<usize> {
+        _check_uint(val, n)?;
+        self.write_all(&val.to_le_bytes()[..n]).map(|()| n)
+    }
+
+    /// Writes an unsigned integer `n` bytes wide in big-endian format to the underlying writer.
+    ///
+    /// Values which don't fit in `n` bytes are rejected with `io::ErrorKind::InvalidInput`.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 16.
+    fn write_uint_be(&mut self, val: u128, n: usize) -> io::Result<usize> {
+        _check_uint(val, n)?;
+        self.write_all(&val.to_be_bytes()[16 - n..]).map(|()| n)
+    }
+
+    /// Writes a two's complement signed integer `n` bytes wide in little-endian format
+    /// to the underlying writer.
+    ///
+    /// Values which don't fit in `n` bytes are rejected with `io::ErrorKind::InvalidInput`.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 16.
+    fn write_int_le(&mut self, val: i128, n: usize) -> io::Result<usize> {
+        _check_int(val, n)?;
+        self.write_all(&val.to_le_bytes()[..n]).map(|()| n)
+    }
+
+    /// Writes a two's complement signed integer `n` bytes wide in big-endian format
+    /// to the underlying writer.
+    ///
+    /// Values which don't fit in `n` bytes are rejected with `io::ErrorKind::InvalidInput`.
+    ///
+    /// # Panics
+    /// Panics if `n` is 0 or greater than 16.
+    fn write_int_be(&mut self, val: i128, n: usize) -> io::Result<usize> {
+        _check_int(val, n)?;
+        self.write_all(&val.to_be_bytes()[16 - n..]).map(|()| n)
+    }
+
+    _write_half_impl!(
+        f32_to_f16, "an IEEE binary16 float",
+        write_f16, write_f16_le, write_f16_be, write_f16_ne
+    );
+    _write_half_impl!(
+        f32_to_bf16, "a bfloat16 float",
+        write_bf16, write_bf16_le, write_bf16_be, write_bf16_ne
+    );
+
+    _write_slice_impl!(i8, "`i8`", write_i8_slice);
+    _write_slice_impl!(u8, "`u8`", write_u8_slice);
+    _write_slice_impl!(i16, "`i16`", write_i16_slice_le, write_i16_slice_be, write_i16_slice_ne);
+    _write_slice_impl!(u16, "`u16`", write_u16_slice_le, write_u16_slice_be, write_u16_slice_ne);
+    _write_slice_impl!(i32, "`i32`", write_i32_slice_le, write_i32_slice_be, write_i32_slice_ne);
+    _write_slice_impl!(u32, "`u32`", write_u32_slice_le, write_u32_slice_be, write_u32_slice_ne);
+    _write_slice_impl!(i64, "`i64`", write_i64_slice_le, write_i64_slice_be, write_i64_slice_ne);
+    _write_slice_impl!(u64, "`u64`", write_u64_slice_le, write_u64_slice_be, write_u64_slice_ne);
+    _write_slice_impl!(
+        i128, "`i128`",
+        write_i128_slice_le, write_i128_slice_be, write_i128_slice_ne
+    );
+    _write_slice_impl!(
+        u128, "`u128`",
+        write_u128_slice_le, write_u128_slice_be, write_u128_slice_ne
+    );
+    _write_slice_impl!(
+        f32, f32::to_bits, "`f32`",
+        write_f32_slice_le, write_f32_slice_be, write_f32_slice_ne
+    );
+    _write_slice_impl!(
+        f64, f64::to_bits, "`f64`",
+        write_f64_slice_le, write_f64_slice_be, write_f64_slice_ne
+    );
+    _write_half_impl!(
+        @slice f32_to_f16, "an IEEE binary16 float",
+        write_f16_slice_le, write_f16_slice_be, write_f16_slice_ne
+    );
+    _write_half_impl!(
+        @slice f32_to_bf16, "a bfloat16 float",
+        write_bf16_slice_le, write_bf16_slice_be, write_bf16_slice_ne
+    );
+
+    /// Writes a fixed-point number's raw bits in the byte order `E` to the underlying writer.
+    #[inline(always)]
+    fn write_fixed<E, I, const FRAC: u32>(&mut self, val: Fixed<I, FRAC>) -> io::Result<usize>
+    where
+        E: ByteOrder,
+        I: FixedBits,
+    {
+        let bytes = E::to_bytes(val.to_bits());
+        self.write_all(bytes.as_ref()).map(|()| size_of::<I>())
+    }
+
+    /// Writes a fixed-point number's raw bits in little-endian format to the underlying writer.
+    #[inline(always)]
+    fn write_fixed_le<I, const FRAC: u32>(&mut self, val: Fixed<I, FRAC>) -> io::Result<usize>
+    where
+        I: FixedBits,
+    {
+        self.write_fixed::<LittleEndian, I, FRAC>(val)
+    }
+
+    /// Writes a fixed-point number's raw bits in big-endian format to the underlying writer.
+    #[inline(always)]
+    fn write_fixed_be<I, const FRAC: u32>(&mut self, val: Fixed<I, FRAC>) -> io::Result<usize>
+    where
+        I: FixedBits,
+    {
+        self.write_fixed::<BigEndian, I, FRAC>(val)
+    }
+
+    /// Writes a fixed-point number's raw bits in native-endian format to the underlying writer.
+    #[inline(always)]
+    fn write_fixed_ne<I, const FRAC: u32>(&mut self, val: Fixed<I, FRAC>) -> io::Result<usize>
+    where
+        I: FixedBits,
+    {
+        self.write_fixed::<NativeEndian, I, FRAC>(val)
+    }
+
+    /// Writes a `u64` as unsigned LEB128 (in as few bytes as possible) to the underlying writer.
+    fn write_uleb128_u64(&mut self, mut val: u64) -> io::Result<usize> {
+        let mut written = 0;
+        loop {
+            let byte = (val & 0x7F) as u8;
+            val >>= 7;
+            if val == 0 {
+                break Ok(written + self.write_u8(byte)?);
+            }
+            written += self.write_u8(byte | 0x80)?;
+        }
+    }
+
+    /// Writes an `i64` as signed LEB128 (in as few bytes as possible) to the underlying writer.
+    fn write_sleb128_i64(&mut self, mut val: i64) -> io::Result<usize> {
+        let mut written = 0;
+        loop {
+            let byte = (val & 0x7F) as u8;
+            val >>= 7;
+            if (val == 0 && byte & 0x40 == 0) || (val == -1 && byte & 0x40 != 0) {
+                break Ok(written + self.write_u8(byte)?);
+            }
+            written += self.write_u8(byte | 0x80)?;
+        }
+    }
+
+    /// Writes an `i64` zigzag encoded as unsigned LEB128 to the underlying writer,
+    /// as used by protobuf's `sint64`.
+    fn write_zigzag_i64(&mut self, val: i64) -> io::Result<usize> {
+        self.write_uleb128_u64(zigzag_encode(val))
+    }
+
+    /// Writes `n` copies of `byte` to the underlying writer, such as to pad out a record.
+    fn write_padding(&mut self, n: usize, byte: u8) -> io::Result<usize> {
+        _write_fill(self, byte, n).map(|()| n)
+    }
+}
+
+impl<W> WritePrimitives for W where W: io::Write {}
+
+/// Reads up to a null-terminator `width` bytes wide, one `width`-byte code unit at a time.
+#[cfg(feature = "alloc")]
+fn _null_chunk_slow<R>(mut rdr: R, max: Option<usize>, width: usize) -> Result<Vec<u8>>
+where
+    R: io::Read,
+{
+    let mut buf = Vec::new();
+    loop {
+        let start = buf.len();
+        buf.resize(start + width, 0);
+        _read_exact_at(&mut rdr, &mut buf[start..], start)?;
+        if buf[start..].iter().all(|&x| x == 0x00) {
+            buf.truncate(start);
+            break Ok(buf);
+        }
+        _check_cstr_len(buf.len() / width, max, width)?;
+    }
+}
+
+/// The one place the `max` contract for null-terminated strings is decided:
+/// at most `max` code units of `width` bytes, not counting the terminator.
+///
+/// Readers call this each time they've found more units which aren't the terminator,
+/// so on failure they've consumed exactly `max + 1` units.
+#[cfg(feature = "alloc")]
+fn _check_cstr_len(len: usize, max: Option<usize>, width: usize) -> Result<()> {
+    match max {
+        Some(max) if len > max => {
+            Err(Error::LimitExceeded { max, string_offset: Some((max * width) as u64) })
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Finds the first null byte, checking a word at a time.
+#[cfg(feature = "alloc")]
+fn _find_null(haystack: &[u8]) -> Option<usize> {
+    const LO: usize = usize::from_ne_bytes([0x01; size_of::<usize>()]);
+    const HI: usize = LO << 7;
+    let mut offset = 0;
+    for chunk in haystack.chunks_exact(size_of::<usize>()) {
+        let mut word = [0u8; size_of::<usize>()];
+        word.copy_from_slice(chunk);
+        let word = usize::from_ne_bytes(word);
+        // nonzero if and only if some byte in the word is zero
+        if word.wrapping_sub(LO) & !word & HI != 0 {
+            break;
+        }
+        offset += size_of::<usize>();
+    }
+    haystack[offset..].iter().position(|&x| x == 0x00).map(|i| offset + i)
+}
+
+#[cfg(feature = "alloc")]
+fn _null_chunk_buf<R>(mut rdr: R, max: Option<usize>) -> Result<Vec<u8>>
+where
+    R: io::BufRead,
+{
+    let mut buf = Vec::new();
+    loop {
+        let available = match rdr.fill_buf() {
+            Ok([]) => break Err(Error::UnexpectedEof { string_offset: Some(buf.len() as u64), source: None }),
+            Ok(available) => available,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => break Err(e.into()),
+        };
+        let (len, done) = match _find_null(available) {
+            Some(len) => (len, true),
+            None => (available.len(), false),
+        };
+        if let Err(e) = _check_cstr_len(buf.len() + len, max, 1) {
+            rdr.consume(max.map_or(0, |max| max + 1 - buf.len()));
+            break Err(e);
+        }
+        buf.extend_from_slice(&available[..len]);
+        if done {
+            rdr.consume(len + 1);
+            break Ok(buf);
+        }
+        rdr.consume(len);
+    }
+}
+
+#[cfg(feature = "alloc")]
+fn _null_chunk<R>(mut rdr: R, max: Option<usize>, width: usize) -> Result<Vec<u8>>
+where
+    R: io::Read + io::Seek,
+{
+    let mut length = 0usize;
+    let mut unit = vec![0u8; width];
+    loop {
+        _read_exact_at(&mut rdr, &mut unit[..], length)?;
+        if unit.iter().all(|&x| x == 0x00) {
+            break;
+        }
+        length += width;
+        _check_cstr_len(length / width, max, width)?;
+    }
+    rdr.seek(io::SeekFrom::Current(-((length + width) as i64)))?;
+
+    let mut buf = vec![0u8; length];
+    _read_exact_at(&mut rdr, &mut buf[..], 0)?;
+    rdr.seek(io::SeekFrom::Current(width as i64))?; // move past null-term
+    Ok(buf)
+}
+
+/// Like `read_exact`, but on running out reports how far into the string that happened,
+/// with `buf` starting `offset` bytes in.
+#[cfg(feature = "alloc")]
+fn _read_exact_at<R>(mut rdr: R, buf: &mut [u8], offset: usize) -> Result<()>
+where
+    R: io::Read,
+{
+    let mut filled = 0;
+    while filled < buf.len() {
+        match rdr.read(&mut buf[filled..]) {
+            Ok(0) => {
+                return Err(Error::UnexpectedEof {
+                    string_offset: Some((offset + filled) as u64),
+                    source: None,
+                })
+            },
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+fn _read_bytes<R>(rdr: R, len: usize) -> Result<Vec<u8>>
+where
+    R: io::Read,
+{
+    let mut buf = vec![0u8; len];
+    _read_exact_at(rdr, &mut buf[..], 0)?;
+    Ok(buf)
+}
+
+#[cfg(feature = "alloc")]
+fn _utf16_units<R>(rdr: R, len: usize, map: fn([u8; 2]) -> u16) -> Result<Vec<u16>>
+where
+    R: io::Read,
+{
+    let mut buf = vec![0u8; len.checked_mul(2).expect("input length overflows usize")];
+    _read_exact_at(rdr, &mut buf[..], 0)?;
+    Ok(buf.chunks_exact(2).map(|x| map([x[0], x[1]])).collect())
+}
+
+/// Like `String::from_utf16`, but reports where the first invalid unit is.
+#[cfg(feature = "alloc")]
+fn _string_from_utf16(units: &[u16]) -> Result<String> {
+    let mut s = String::with_capacity(units.len());
+    let mut offset = 0;
+    for c in char::decode_utf16(units.iter().copied()) {
+        match c {
+            Ok(c) => {
+                s.push(c);
+                offset += c.len_utf16() * 2;
+            },
+            Err(_) => return Err(Error::InvalidUtf16 { string_offset: Some(offset as u64) }),
+        }
+    }
+    Ok(s)
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! _read_utf16_impl {
+    ($strict: ident, $lossy: ident, $from_bytes: ident, $order: literal) => {
+        #[doc = "Reads a UTF-16 ("] #[doc = $order] #[doc = ") encoded string from the underlying"]
+        #[doc = "reader with a given length (in 16-bit integers, **NOT** bytes)."]
+        #[doc = ""]
+        #[doc = "# Panics"]
+        #[doc = "Panics if `len * 2` overflows usize."]
+        fn $strict(&mut self, len: usize) -> Result<String> {
+            _string_from_utf16(&_utf16_units(self, len, u16::$from_bytes)?)
+        }
+
+        #[doc = "Reads a UTF-16 ("] #[doc = $order] #[doc = ") encoded string from the underlying"]
+        #[doc = "reader with a given length (in 16-bit integers, **NOT** bytes)."]
+        #[doc = ""]
+        #[doc = "If any invalid UTF-16 is present, the bad chars are replaced"]
+        #[doc = "with U+FFFD REPLACEMENT CHARACTER, which looks like this: �"]
+        #[doc = ""]
+        #[doc = "# Panics"]
+        #[doc = "Panics if `len * 2` overflows usize."]
+        fn $lossy(&mut self, len: usize) -> Result<String> {
+            Ok(String::from_utf16_lossy(&_utf16_units(self, len, u16::$from_bytes)?))
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! _read_wide_cstr_impl {
+    (
+        $cstr: ident, $lossy: ident, $fast: ident, $lossy_fast: ident,
+        $enc: ty, $order: literal
+    ) => {
+        #[doc = "Reads a UTF-16 ("] #[doc = $order]
+        #[doc = ") encoded, null-terminated string from the underlying reader."]
+        #[doc = "The terminator is a 16-bit integer of zero."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` 16-bit integers"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` of them."]
+        fn $cstr(&mut self, max: Option<usize>) -> Result<String> {
+            self.read_cstr::<$enc>(max)
+        }
+
+        #[doc = "Reads a UTF-16 ("] #[doc = $order]
+        #[doc = ") encoded, null-terminated string from the underlying reader."]
+        #[doc = "The terminator is a 16-bit integer of zero."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` 16-bit integers"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` of them."]
+        #[doc = ""]
+        #[doc = "If any invalid UTF-16 is present, the bad chars are replaced"]
+        #[doc = "with U+FFFD REPLACEMENT CHARACTER, which looks like this: �"]
+        fn $lossy(&mut self, max: Option<usize>) -> Result<String> {
+            self.read_cstr_lossy::<$enc>(max)
+        }
+
+        #[doc = "Reads a UTF-16 ("] #[doc = $order]
+        #[doc = ") encoded, null-terminated string from the underlying reader."]
+        #[doc = "The terminator is a 16-bit integer of zero."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` 16-bit integers"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` of them."]
+        #[doc = ""]
+        #[doc = "*This is functionally identical to the same method without `_fast`,"]
+        #[doc = "it's just a lot faster, but only works on readers that have `io::Seek`.*"]
+        fn $fast(&mut self, max: Option<usize>) -> Result<String>
+        where
+            Self: io::Seek,
+        {
+            self.read_cstr_fast::<$enc>(max)
+        }
+
+        #[doc = "Reads a UTF-16 ("] #[doc = $order]
+        #[doc = ") encoded, null-terminated string from the underlying reader."]
+        #[doc = "The terminator is a 16-bit integer of zero."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` 16-bit integers"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` of them."]
+        #[doc = ""]
+        #[doc = "If any invalid UTF-16 is present, the bad chars are replaced"]
+        #[doc = "with U+FFFD REPLACEMENT CHARACTER, which looks like this: �"]
+        #[doc = ""]
+        #[doc = "*This is functionally identical to the same method without `_fast`,"]
+        #[doc = "it's just a lot faster, but only works on readers that have `io::Seek`.*"]
+        fn $lossy_fast(&mut self, max: Option<usize>) -> Result<String>
+        where
+            Self: io::Seek,
+        {
+            self.read_cstr_lossy_fast::<$enc>(max)
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! _read_utf32_impl {
+    ($str: ident, $cstr: ident, $enc: ty, $order: literal) => {
+        #[doc = "Reads a UTF-32 ("] #[doc = $order] #[doc = ") encoded string from the underlying"]
+        #[doc = "reader with a given length (in 32-bit integers, **NOT** bytes)."]
+        #[doc = ""]
+        #[doc = "# Panics"]
+        #[doc = "Panics if `len * 4` overflows usize."]
+        fn $str(&mut self, len: usize) -> Result<String> {
+            self.read_str::<$enc>(len)
+        }
+
+        #[doc = "Reads a UTF-32 ("] #[doc = $order]
+        #[doc = ") encoded, null-terminated string from the underlying reader."]
+        #[doc = "The terminator is a 32-bit integer of zero."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` 32-bit integers"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` of them."]
+        fn $cstr(&mut self, max: Option<usize>) -> Result<String> {
+            self.read_cstr::<$enc>(max)
+        }
+    };
+}
+
+macro_rules! _write_wide_impl {
+    ($cstr: ident, $str: ident, $write: ident, $len: expr, $name: literal) => {
+        #[doc = "Writes a string to the underlying writer as"] #[doc = $name]
+        #[doc = "followed by a null-terminator of the same width."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than that many integers (excluding the"]
+        #[doc = "terminator) are rejected with `io::ErrorKind::InvalidInput`,"]
+        #[doc = "as are strings containing a null character."]
+        fn $cstr(&mut self, s: &str, max: Option<usize>) -> io::Result<usize>
+        where
+            Self: WritePrimitives,
+        {
+            if s.contains('\0') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string contains a null byte",
+                ));
+            }
+            if max.is_some_and(|max| $len(s) > max) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string exceeds max length",
+                ));
+            }
+            let written = self.$str(s)?;
+            Ok(written + self.$write(0)?)
+        }
+    };
+}
+
+#[cfg(feature = "alloc")]
+macro_rules! _read_codepage_impl {
+    ($str: ident, $cstr: ident, $decode: path, $name: literal) => {
+        #[doc = "Reads a "] #[doc = $name]
+        #[doc = "encoded string from the underlying reader with a given length (in bytes)."]
+        #[doc = ""]
+        #[doc = "Every byte stands for some character, so this can't fail on the data itself."]
+        fn $str(&mut self, len: usize) -> Result<String> {
+            Ok(_read_bytes(self, len)?.into_iter().map($decode).collect())
+        }
+
+        #[doc = "Reads a "] #[doc = $name]
+        #[doc = "encoded, null-terminated string from the underlying reader."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` bytes"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` bytes."]
+        fn $cstr(&mut self, max: Option<usize>) -> Result<String> {
+            Ok(_null_chunk_slow(self, max, 1)?.into_iter().map($decode).collect())
+        }
+    };
+}
+
+macro_rules! _write_codepage_impl {
+    ($str: ident, $cstr: ident, $encode: path, $name: literal) => {
+        #[doc = "Writes a string to the underlying writer as"] #[doc = $name]
+        #[doc = "(without a length or null-terminator)."]
+        #[doc = ""]
+        #[doc = "Strings with characters it can't represent are rejected, before anything is"]
+        #[doc = "written, with `io::ErrorKind::InvalidInput` wrapping an"]
+        #[doc = "[Error::Unencodable](enum.Error.html#variant.Unencodable)"]
+        #[doc = "(where `std` is enabled)."]
+        fn $str(&mut self, s: &str) -> io::Result<usize> {
+            _write_codepage(self, s, $encode)
+        }
+
+        #[doc = "Writes a string to the underlying writer as"] #[doc = $name]
+        #[doc = "followed by a null-terminator."]
+        #[doc = ""]
+        #[doc = "Strings with characters it can't represent are rejected, before anything is"]
+        #[doc = "written, with `io::ErrorKind::InvalidInput` wrapping an"]
+        #[doc = "[Error::Unencodable](enum.Error.html#variant.Unencodable)"]
+        #[doc = "(where `std` is enabled)."]
+        #[doc = "So are strings longer than `max` bytes (excluding the terminator),"]
+        #[doc = "if it's provided, and strings containing a null byte."]
+        fn $cstr(&mut self, s: &str, max: Option<usize>) -> io::Result<usize> {
+            if s.contains('\0') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string contains a null byte",
+                ));
+            }
+            if max.is_some_and(|max| s.chars().count() > max) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string exceeds max length",
+                ));
+            }
+            let written = _write_codepage(&mut *self, s, $encode)?;
+            self.write_all(&[0x00]).map(|()| written + 1)
+        }
+    };
+}
+
+fn _write_codepage<W>(mut wtr: W, s: &str, encode: fn(char) -> Option<u8>) -> io::Result<usize>
+where
+    W: io::Write,
+{
+    if let Some((i, ch)) = s.chars().enumerate().find(|&(_, ch)| encode(ch).is_none()) {
+        return Err(Error::Unencodable { ch, string_offset: Some(i as u64) }.into());
+    }
+    let mut buf = [0u8; _SLICE_BUF_LEN];
+    let mut chars = s.chars();
+    let mut written = 0;
+    loop {
+        let mut len = 0;
+        for (x, ch) in buf.iter_mut().zip(&mut chars) {
+            *x = encode(ch).unwrap_or_default();
+            len += 1;
+        }
+        if len == 0 {
+            break Ok(written);
+        }
+        wtr.write_all(&buf[..len])?;
+        written += len;
+    }
+}
+
+#[cfg(feature = "cjk")]
+macro_rules! _read_multibyte_impl {
+    (
+        $str: ident, $lossy: ident, $cstr: ident, $cstr_lossy: ident,
+        $enc: path, $name: literal
+    ) => {
+        #[doc = "Reads a "] #[doc = $name]
+        #[doc = "encoded string from the underlying reader with a given length (in bytes)."]
+        #[doc = ""]
+        #[doc = "Requires the `cjk` feature."]
+        fn $str(&mut self, len: usize) -> Result<String> {
+            multibyte::decode($enc, &_read_bytes(self, len)?)
+        }
+
+        #[doc = "Reads a "] #[doc = $name]
+        #[doc = "encoded string from the underlying reader with a given length (in bytes)."]
+        #[doc = ""]
+        #[doc = "If any invalid byte sequences are present, they're replaced"]
+        #[doc = "with U+FFFD REPLACEMENT CHARACTER, which looks like this: �"]
+        #[doc = ""]
+        #[doc = "Requires the `cjk` feature."]
+        fn $lossy(&mut self, len: usize) -> Result<String> {
+            Ok(multibyte::decode_lossy($enc, &_read_bytes(self, len)?))
+        }
+
+        #[doc = "Reads a "] #[doc = $name]
+        #[doc = "encoded, null-terminated string from the underlying reader."]
+        #[doc = ""]
+        #[doc = "The encoding never uses a null byte as the second half of a character,"]
+        #[doc = "so the first one always ends the string."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` bytes"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` bytes."]
+        #[doc = ""]
+        #[doc = "Requires the `cjk` feature."]
+        fn $cstr(&mut self, max: Option<usize>) -> Result<String> {
+            multibyte::decode($enc, &_null_chunk_slow(self, max, 1)?)
+        }
+
+        #[doc = "Reads a "] #[doc = $name]
+        #[doc = "encoded, null-terminated string from the underlying reader."]
+        #[doc = ""]
+        #[doc = "If any invalid byte sequences are present, they're replaced"]
+        #[doc = "with U+FFFD REPLACEMENT CHARACTER, which looks like this: �"]
+        #[doc = "A lead byte right before the terminator counts as one."]
+        #[doc = ""]
+        #[doc = "If `max` is provided, strings longer than `max` bytes"]
+        #[doc = "(not counting the terminator) are rejected with"]
+        #[doc = "[Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)"]
+        #[doc = "after reading `max + 1` bytes."]
+        #[doc = ""]
+        #[doc = "Requires the `cjk` feature."]
+        fn $cstr_lossy(&mut self, max: Option<usize>) -> Result<String> {
+            Ok(multibyte::decode_lossy($enc, &_null_chunk_slow(self, max, 1)?))
+        }
+    };
+}
+
+#[cfg(feature = "cjk")]
+macro_rules! _write_multibyte_impl {
+    ($str: ident, $cstr: ident, $enc: path, $name: literal) => {
+        #[doc = "Writes a string to the underlying writer as"] #[doc = $name]
+        #[doc = "(without a length or null-terminator)."]
+        #[doc = ""]
+        #[doc = "Strings with characters it can't represent are rejected, before anything is"]
+        #[doc = "written, with `io::ErrorKind::InvalidInput` wrapping an"]
+        #[doc = "[Error::Unencodable](enum.Error.html#variant.Unencodable)"]
+        #[doc = "(where `std` is enabled)."]
+        #[doc = ""]
+        #[doc = "Requires the `cjk` feature."]
+        fn $str(&mut self, s: &str) -> io::Result<usize> {
+            let buf = multibyte::encode($enc, s)?;
+            self.write_all(&buf).map(|()| buf.len())
+        }
+
+        #[doc = "Writes a string to the underlying writer as"] #[doc = $name]
+        #[doc = "followed by a null-terminator."]
+        #[doc = ""]
+        #[doc = "Strings with characters it can't represent are rejected, before anything is"]
+        #[doc = "written, with `io::ErrorKind::InvalidInput` wrapping an"]
+        #[doc = "[Error::Unencodable](enum.Error.html#variant.Unencodable)"]
+        #[doc = "(where `std` is enabled)."]
+        #[doc = "So are strings longer than `max` bytes once encoded (excluding the terminator),"]
+        #[doc = "if it's provided, and strings containing a null byte."]
+        #[doc = ""]
+        #[doc = "Requires the `cjk` feature."]
+        fn $cstr(&mut self, s: &str, max: Option<usize>) -> io::Result<usize> {
+            if s.contains('\0') {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string contains a null byte",
+                ));
+            }
+            let mut buf = multibyte::encode($enc, s)?;
+            if max.is_some_and(|max| buf.len() > max) {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string exceeds max length",
+                ));
+            }
+            buf.push(0x00);
+            self.write_all(&buf).map(|()| buf.len())
+        }
+    };
+}
+
+/// The integer type a length-prefixed string stores its length as.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum LengthPrefix {
+    /// A single `u8`.
+    U8,
+    /// A little-endian `u16`.
+    U16Le,
+    /// A big-endian `u16`.
+    U16Be,
+    /// A little-endian `u32`.
+    U32Le,
+    /// A big-endian `u32`.
+    U32Be,
+    /// An unsigned LEB128 varint.
+    Uleb128,
+}
+
+#[cfg(feature = "alloc")]
+fn _read_prefix<R>(mut rdr: R, prefix: LengthPrefix, max: Option<usize>) -> Result<usize>
+where
+    R: io::Read,
+{
+    let len = match prefix {
+        LengthPrefix::U8 => u64::from(rdr.read_u8()?),
+        LengthPrefix::U16Le => u64::from(rdr.read_u16_le()?),
+        LengthPrefix::U16Be => u64::from(rdr.read_u16_be()?),
+        LengthPrefix::U32Le => u64::from(rdr.read_u32_le()?),
+        LengthPrefix::U32Be => u64::from(rdr.read_u32_be()?),
+        LengthPrefix::Uleb128 => rdr.read_uleb128_u64()?,
+    };
+    match (usize::try_from(len), max) {
+        (Ok(len), Some(max)) if len > max => Err(Error::LimitExceeded { max, string_offset: None }),
+        (Ok(len), _) => Ok(len),
+        (Err(_), max) => Err(Error::LimitExceeded { max: max.unwrap_or(usize::MAX), string_offset: None }),
+    }
+}
+
+fn _write_prefix<W>(
+    mut wtr: W,
+    prefix: LengthPrefix,
+    len: usize,
+    max: Option<usize>,
+) -> io::Result<usize>
+where
+    W: io::Write,
+{
+    let too_long = || io::Error::new(io::ErrorKind::InvalidInput, "string exceeds max length");
+    if max.is_some_and(|max| len > max) {
+        return Err(too_long());
+    }
+    match prefix {
+        LengthPrefix::U8 => wtr.write_u8(u8::try_from(len).map_err(|_| too_long())?),
+        LengthPrefix::U16Le => wtr.write_u16_le(u16::try_from(len).map_err(|_| too_long())?),
+        LengthPrefix::U16Be => wtr.write_u16_be(u16::try_from(len).map_err(|_| too_long())?),
+        LengthPrefix::U32Le => wtr.write_u32_le(u32::try_from(len).map_err(|_| too_long())?),
+        LengthPrefix::U32Be => wtr.write_u32_be(u32::try_from(len).map_err(|_| too_long())?),
+        LengthPrefix::Uleb128 => wtr.write_uleb128_u64(len as u64),
+    }
+}
+
+/// How a fixed-width string field fills the space after the string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Padding {
+    /// Null bytes. When reading, the string ends at the first null byte
+    /// and whatever comes after it is ignored, as C often leaves garbage there.
+    Null,
+    /// Null bytes, like [Null](#variant.Null), but when reading, everything after the first
+    /// null byte is checked to be zero as well, failing with
+    /// [Error::InvalidPadding](enum.Error.html#variant.InvalidPadding) if it isn't.
+    Zeroes,
+    /// Spaces. When reading, trailing spaces are trimmed off,
+    /// after cutting the string at the first null byte if there is one.
+    Space,
+}
+
+/// Finds where the string in a fixed-width field ends.
+#[cfg(feature = "alloc")]
+fn _fixed_str_len(field: &[u8], padding: Padding) -> Result<usize> {
+    let len = field.iter().position(|&x| x == 0x00).unwrap_or(field.len());
+    match padding {
+        Padding::Null => Ok(len),
+        Padding::Zeroes => match field[len..].iter().position(|&x| x != 0x00) {
+            Some(i) => Err(Error::InvalidPadding { padding_offset: Some((len + i) as u64) }),
+            None => Ok(len),
+        },
+        Padding::Space => Ok(field[..len].iter().rposition(|&x| x != b' ').map_or(0, |i| i + 1)),
+    }
+}
+
+/// How far `pos` is from the next multiple of `align`.
+fn _padding_len(pos: u64, align: usize) -> usize {
+    assert!(align != 0, "alignment must be nonzero");
+    let rem = (pos % align as u64) as usize;
+    (align - rem) % align
+}
+
+/// Writes `byte` `n` times.
+fn _write_fill<W>(mut wtr: W, byte: u8, mut n: usize) -> io::Result<()>
+where
+    W: io::Write,
+{
+    let buf = [byte; _SLICE_BUF_LEN];
+    while n > 0 {
+        let len = n.min(buf.len());
+        wtr.write_all(&buf[..len])?;
+        n -= len;
+    }
+    Ok(())
+}
+
+#[cfg(feature = "alloc")]
+/// Provides methods for reading strings of various encodings.
+///
+/// These return minio's own [Error](enum.Error.html), which tells bad data apart from I/O
+/// errors, and converts to an `io::Error` with `?` where that's needed.
+///
+/// The null-terminated UTF-8 readers need `io::BufRead`, so they can scan for the terminator
+/// without reading past it. Other null-terminated readers read one code unit at a time, as
+/// that's all `io::Read` allows, and most have `_fast` versions for readers with `io::Seek`.
+pub trait ReadStrings: io::Read {
+    /// Reads a UTF-8 encoded string from the underlying reader with a given length (in bytes).
+    fn read_str_utf8(&mut self, len: usize) -> Result<String> {
+        Ok(String::from_utf8(_read_bytes(self, len)?)?)
+    }
+
+    /// Reads a UTF-8 encoded string from the underlying reader with a given length (in bytes).
     ///
+    /// # Safety
     /// The validity of the UTF-8 is not checked, therefore this is marked **unsafe**.
-    unsafe fn read_cstr_utf8_unchecked(&mut self, max: Option<usize>) -> io::Result<String> {
-        _null_chunk_slow(self, max).map(|buf| String::from_utf8_unchecked(buf))
+    unsafe fn read_str_utf8_unchecked(&mut self, len: usize) -> Result<String> {
+        Ok(String::from_utf8_unchecked(_read_bytes(self, len)?))
     }
 
-    /// **If your reader has `io::Seek`, use
-    /// [read_cstr_utf8_lossy_fast](#method.read_cstr_utf8_lossy_fast)
-    /// instead.**
+    /// Reads a UTF-8 encoded string from the underlying reader with a given length (in bytes).
     ///
-    /// Reads a UTF-8 encoded, null-terminated string from the underlying reader.
+    /// If any invalid UTF-8 is present, the bad chars are replaced with
+    /// U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    fn read_str_utf8_lossy(&mut self, len: usize) -> Result<String> {
+        Ok(String::from_utf8_lossy(&_read_bytes(self, len)?).into_owned())
+    }
+
+    _read_utf16_impl!(read_str_utf16_le, read_str_utf16_lossy_le, from_le_bytes, "little-endian");
+    _read_utf16_impl!(read_str_utf16_be, read_str_utf16_lossy_be, from_be_bytes, "big-endian");
+    _read_utf16_impl!(read_str_utf16_ne, read_str_utf16_lossy_ne, from_ne_bytes, "native-endian");
+
+    /// Reads a UTF-16 encoded string from the underlying reader with a given length
+    /// (in 16-bit integers, **NOT** bytes).
+    ///
+    /// # Panics
+    /// Panics if `len * 2` overflows usize.
+    #[deprecated(note = "byte order is implicit, use `read_str_utf16_le`, `_be` or `_ne` instead")]
+    #[inline(always)]
+    fn read_str_utf16(&mut self, len: usize) -> Result<String> {
+        self.read_str_utf16_ne(len)
+    }
+
+    /// Reads a UTF-16 encoded string from the underlying reader with a given length
+    /// (in 16-bit integers, **NOT** bytes).
+    ///
+    /// If any invalid UTF-16 is present, the bad chars are replaced
+    /// with U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    ///
+    /// # Panics
+    /// Panics if `len * 2` overflows usize.
+    #[deprecated(
+        note = "byte order is implicit, use `read_str_utf16_lossy_le`, `_be` or `_ne` instead"
+    )]
+    #[inline(always)]
+    fn read_str_utf16_lossy(&mut self, len: usize) -> Result<String> {
+        self.read_str_utf16_lossy_ne(len)
+    }
+
+    /// Reads a UTF-8 encoded, null-terminated string from the underlying reader,
+    /// scanning its buffer for the terminator and consuming exactly through it.
     ///
-    /// If `max` is provided, it'll only try to read that many bytes before erroring (giving up).
+    /// If `max` is provided, strings longer than `max` bytes (not counting the terminator) are
+    /// rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` bytes.
+    ///
+    /// *Readers without `io::BufRead` can use
+    /// [read_cstr_utf8_fast](#method.read_cstr_utf8_fast) if they have `io::Seek`,
+    /// or otherwise [read_cstr](#method.read_cstr) with [Utf8](struct.Utf8.html),
+    /// which reads a byte at a time.*
+    fn read_cstr_utf8(&mut self, max: Option<usize>) -> Result<String>
+    where
+        Self: io::BufRead,
+    {
+        Ok(String::from_utf8(_null_chunk_buf(self, max)?)?)
+    }
+
+    /// Reads a UTF-8 encoded, null-terminated string from the underlying reader,
+    /// scanning its buffer for the terminator and consuming exactly through it.
+    ///
+    /// If `max` is provided, strings longer than `max` bytes (not counting the terminator) are
+    /// rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` bytes.
+    ///
+    /// # Safety
+    /// The validity of the UTF-8 is not checked, therefore this is marked **unsafe**.
+    ///
+    /// *Readers without `io::BufRead` can use
+    /// [read_cstr_utf8_unchecked_fast](#method.read_cstr_utf8_unchecked_fast)
+    /// if they have `io::Seek`.*
+    unsafe fn read_cstr_utf8_unchecked(&mut self, max: Option<usize>) -> Result<String>
+    where
+        Self: io::BufRead,
+    {
+        _null_chunk_buf(self, max).map(|buf| String::from_utf8_unchecked(buf))
+    }
+
+    /// Reads a UTF-8 encoded, null-terminated string from the underlying reader,
+    /// scanning its buffer for the terminator and consuming exactly through it.
+    ///
+    /// If `max` is provided, strings longer than `max` bytes (not counting the terminator) are
+    /// rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` bytes.
     ///
     /// If any invalid UTF-8 is present, the bad chars are replaced with
     /// U+FFFD REPLACEMENT CHARACTER, which looks like this: �
-    fn read_cstr_utf8_lossy(&mut self, max: Option<usize>) -> io::Result<String> {
-        _null_chunk_slow(self, max).map(|buf| String::from_utf8_lossy(&*buf).into_owned())
+    ///
+    /// *Readers without `io::BufRead` can use
+    /// [read_cstr_utf8_lossy_fast](#method.read_cstr_utf8_lossy_fast) if they have `io::Seek`,
+    /// or otherwise [read_cstr_lossy](#method.read_cstr_lossy) with [Utf8](struct.Utf8.html),
+    /// which reads a byte at a time.*
+    fn read_cstr_utf8_lossy(&mut self, max: Option<usize>) -> Result<String>
+    where
+        Self: io::BufRead,
+    {
+        Ok(String::from_utf8_lossy(&_null_chunk_buf(self, max)?).into_owned())
     }
 
     /// Reads a UTF-8 encoded, null-terminated string from the underlying reader.
     ///
-    /// If `max` is provided, it'll only try to read that many bytes before erroring (giving up).
+    /// If `max` is provided, strings longer than `max` bytes (not counting the terminator) are
+    /// rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` bytes.
     ///
     /// *This is functionally identical to
     /// [read_cstr_utf8](#method.read_cstr_utf8),
-    /// it's just a lot faster, but only works on readers that have `io::Seek`.*
-    fn read_cstr_utf8_fast(
-        &mut self,
-        max: Option<usize>,
-    ) -> io::Result<Result<String, std::string::FromUtf8Error>>
+    /// but works on readers that have `io::Seek` instead of `io::BufRead`.*
+    fn read_cstr_utf8_fast(&mut self, max: Option<usize>) -> Result<String>
     where
         Self: ReadPrimitives + io::Seek,
     {
-        let chunk = _null_chunk(self, max)?;
-        Ok(String::from_utf8(chunk))
+        Ok(String::from_utf8(_null_chunk(self, max, 1)?)?)
     }
 
     /// Reads a UTF-8 encoded, null-terminated string from the underlying reader.
     ///
-    /// If `max` is provided, it'll only try to read that many bytes before erroring (giving up).
+    /// If `max` is provided, strings longer than `max` bytes (not counting the terminator) are
+    /// rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` bytes.
     ///
+    /// # Safety
     /// The validity of the UTF-8 is not checked, therefore this is marked **unsafe**.
     ///
     /// *This is functionally identical to
     /// [read_cstr_utf8_unchecked](#method.read_cstr_utf8_unchecked),
-    /// it's just a lot faster, but only works on readers that have `io::Seek`.*
-    unsafe fn read_cstr_utf8_unchecked_fast(&mut self, max: Option<usize>) -> io::Result<String>
+    /// but works on readers that have `io::Seek` instead of `io::BufRead`.*
+    unsafe fn read_cstr_utf8_unchecked_fast(&mut self, max: Option<usize>) -> Result<String>
     where
         Self: ReadPrimitives + io::Seek,
     {
-        _null_chunk(self, max).map(|buf| String::from_utf8_unchecked(buf))
+        _null_chunk(self, max, 1).map(|buf| String::from_utf8_unchecked(buf))
     }
 
     /// Reads a UTF-8 encoded, null-terminated string from the underlying reader.
     ///
-    /// If `max` is provided, it'll only try to read that many bytes before erroring (giving up).
+    /// If `max` is provided, strings longer than `max` bytes (not counting the terminator) are
+    /// rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` bytes.
     ///
     /// If any invalid UTF-8 is present, it's replaced with
     /// U+FFFD REPLACEMENT CHARACTER, which looks like this: �
     ///
     /// *This is functionally identical to
     /// [read_cstr_utf8_lossy](#method.read_cstr_utf8_lossy),
-    /// it's just a lot faster, but only works on readers that have `io::Seek`.*
-    fn read_cstr_utf8_lossy_fast(&mut self, max: Option<usize>) -> io::Result<String>
+    /// but works on readers that have `io::Seek` instead of `io::BufRead`.*
+    fn read_cstr_utf8_lossy_fast(&mut self, max: Option<usize>) -> Result<String>
     where
         Self: ReadPrimitives + io::Seek,
     {
-        let chunk = _null_chunk(self, max)?;
+        let chunk = _null_chunk(self, max, 1)?;
         Ok(String::from_utf8_lossy(&chunk).into_owned())
     }
+
+    _read_wide_cstr_impl!(
+        read_cstr_utf16_le, read_cstr_utf16_lossy_le,
+        read_cstr_utf16_fast_le, read_cstr_utf16_lossy_fast_le,
+        Utf16Le, "little-endian"
+    );
+    _read_wide_cstr_impl!(
+        read_cstr_utf16_be, read_cstr_utf16_lossy_be,
+        read_cstr_utf16_fast_be, read_cstr_utf16_lossy_fast_be,
+        Utf16Be, "big-endian"
+    );
+
+    _read_utf32_impl!(read_str_utf32_le, read_cstr_utf32_le, Utf32Le, "little-endian");
+    _read_utf32_impl!(read_str_utf32_be, read_cstr_utf32_be, Utf32Be, "big-endian");
+
+    /// Reads a UTF-8 encoded string from a fixed-width field of `width` bytes,
+    /// such as a C `char name[32]`, cutting off the padding as described by `padding`.
+    ///
+    /// All `width` bytes are always read, even when the string ends early.
+    fn read_fixed_str_utf8(&mut self, width: usize, padding: Padding) -> Result<String> {
+        let mut field = _read_bytes(self, width)?;
+        field.truncate(_fixed_str_len(&field, padding)?);
+        Ok(String::from_utf8(field)?)
+    }
+
+    /// Reads a UTF-8 encoded string from the underlying reader, preceded by its length
+    /// (in bytes) stored as described by `prefix`.
+    ///
+    /// If `max` is provided, lengths over it are rejected with
+    /// [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded) before anything is allocated.
+    fn read_pstr_utf8(
+        &mut self,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> Result<String> {
+        let len = _read_prefix(&mut *self, prefix, max)?;
+        self.read_str_utf8(len)
+    }
+
+    /// Reads a UTF-8 encoded string from the underlying reader, preceded by its length
+    /// (in bytes) stored as described by `prefix`.
+    ///
+    /// If `max` is provided, lengths over it are rejected with
+    /// [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded) before anything is allocated.
+    ///
+    /// If any invalid UTF-8 is present, the bad chars are replaced with
+    /// U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    fn read_pstr_utf8_lossy(
+        &mut self,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> Result<String> {
+        let len = _read_prefix(&mut *self, prefix, max)?;
+        self.read_str_utf8_lossy(len)
+    }
+
+    /// Reads a little-endian UTF-16 encoded string from the underlying reader, preceded by its
+    /// length (in 16-bit integers, **NOT** bytes) stored as described by `prefix`.
+    ///
+    /// If `max` is provided, lengths over it are rejected with
+    /// [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded) before anything is allocated.
+    fn read_pstr_utf16_le(
+        &mut self,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> Result<String> {
+        let len = _read_prefix(&mut *self, prefix, max)?;
+        self.read_str_utf16_le(len)
+    }
+
+    /// Reads a big-endian UTF-16 encoded string from the underlying reader, preceded by its
+    /// length (in 16-bit integers, **NOT** bytes) stored as described by `prefix`.
+    ///
+    /// If `max` is provided, lengths over it are rejected with
+    /// [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded) before anything is allocated.
+    fn read_pstr_utf16_be(
+        &mut self,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> Result<String> {
+        let len = _read_prefix(&mut *self, prefix, max)?;
+        self.read_str_utf16_be(len)
+    }
+
+    /// Reads a little-endian UTF-16 encoded string from the underlying reader, preceded by its
+    /// length (in 16-bit integers, **NOT** bytes) stored as described by `prefix`.
+    ///
+    /// If `max` is provided, lengths over it are rejected with
+    /// [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded) before anything is allocated.
+    ///
+    /// If any invalid UTF-16 is present, the bad chars are replaced
+    /// with U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    fn read_pstr_utf16_lossy_le(
+        &mut self,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> Result<String> {
+        let len = _read_prefix(&mut *self, prefix, max)?;
+        self.read_str_utf16_lossy_le(len)
+    }
+
+    /// Reads a big-endian UTF-16 encoded string from the underlying reader, preceded by its
+    /// length (in 16-bit integers, **NOT** bytes) stored as described by `prefix`.
+    ///
+    /// If `max` is provided, lengths over it are rejected with
+    /// [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded) before anything is allocated.
+    ///
+    /// If any invalid UTF-16 is present, the bad chars are replaced
+    /// with U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    fn read_pstr_utf16_lossy_be(
+        &mut self,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> Result<String> {
+        let len = _read_prefix(&mut *self, prefix, max)?;
+        self.read_str_utf16_lossy_be(len)
+    }
+
+    _read_codepage_impl!(
+        read_str_latin1, read_cstr_latin1,
+        codepage::latin1_decode, "Latin-1 (ISO 8859-1)"
+    );
+    _read_codepage_impl!(
+        read_str_cp1252, read_cstr_cp1252,
+        codepage::cp1252_decode, "Windows-1252"
+    );
+    _read_codepage_impl!(
+        read_str_cp437, read_cstr_cp437,
+        codepage::cp437_decode, "code page 437"
+    );
+
+    #[cfg(feature = "cjk")]
+    _read_multibyte_impl!(
+        read_str_shift_jis, read_str_shift_jis_lossy,
+        read_cstr_shift_jis, read_cstr_shift_jis_lossy,
+        encoding_rs::SHIFT_JIS, "Shift-JIS"
+    );
+    #[cfg(feature = "cjk")]
+    _read_multibyte_impl!(
+        read_str_gbk, read_str_gbk_lossy,
+        read_cstr_gbk, read_cstr_gbk_lossy,
+        encoding_rs::GBK, "GBK"
+    );
+    #[cfg(feature = "cjk")]
+    _read_multibyte_impl!(
+        read_str_euc_kr, read_str_euc_kr_lossy,
+        read_cstr_euc_kr, read_cstr_euc_kr_lossy,
+        encoding_rs::EUC_KR, "EUC-KR"
+    );
+
+    /// Reads a string in the encoding `E` from the underlying reader with a given length
+    /// (in code units of `E::UNIT_WIDTH` bytes).
+    ///
+    /// # Panics
+    /// Panics if `len * E::UNIT_WIDTH` overflows usize.
+    fn read_str<E: Encoding>(&mut self, len: usize) -> Result<String> {
+        let len = len.checked_mul(E::UNIT_WIDTH).expect("input length overflows usize");
+        E::decode(&_read_bytes(self, len)?)
+    }
+
+    /// Reads a string in the encoding `E` from the underlying reader with a given length
+    /// (in code units of `E::UNIT_WIDTH` bytes).
+    ///
+    /// Anything invalid is replaced with U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    ///
+    /// # Panics
+    /// Panics if `len * E::UNIT_WIDTH` overflows usize.
+    fn read_str_lossy<E: Encoding>(&mut self, len: usize) -> Result<String> {
+        let len = len.checked_mul(E::UNIT_WIDTH).expect("input length overflows usize");
+        Ok(E::decode_lossy(&_read_bytes(self, len)?))
+    }
+
+    /// Reads a null-terminated string in the encoding `E` from the underlying reader.
+    /// The terminator is one code unit of `E::UNIT_WIDTH` zero bytes.
+    ///
+    /// If `max` is provided, strings longer than `max` code units (not counting the terminator)
+    /// are rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` code units.
+    fn read_cstr<E: Encoding>(&mut self, max: Option<usize>) -> Result<String> {
+        E::decode(&_null_chunk_slow(self, max, E::UNIT_WIDTH)?)
+    }
+
+    /// Reads a null-terminated string in the encoding `E` from the underlying reader.
+    /// The terminator is one code unit of `E::UNIT_WIDTH` zero bytes.
+    ///
+    /// Anything invalid is replaced with U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    ///
+    /// If `max` is provided, strings longer than `max` code units (not counting the terminator)
+    /// are rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` code units.
+    fn read_cstr_lossy<E: Encoding>(&mut self, max: Option<usize>) -> Result<String> {
+        Ok(E::decode_lossy(&_null_chunk_slow(self, max, E::UNIT_WIDTH)?))
+    }
+
+    /// Reads a null-terminated string in the encoding `E` from the underlying reader.
+    /// The terminator is one code unit of `E::UNIT_WIDTH` zero bytes.
+    ///
+    /// If `max` is provided, strings longer than `max` code units (not counting the terminator)
+    /// are rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` code units.
+    ///
+    /// *This is functionally identical to
+    /// [read_cstr](#method.read_cstr),
+    /// it's just a lot faster, but only works on readers that have `io::Seek`.*
+    fn read_cstr_fast<E: Encoding>(&mut self, max: Option<usize>) -> Result<String>
+    where
+        Self: io::Seek,
+    {
+        E::decode(&_null_chunk(self, max, E::UNIT_WIDTH)?)
+    }
+
+    /// Reads a null-terminated string in the encoding `E` from the underlying reader.
+    /// The terminator is one code unit of `E::UNIT_WIDTH` zero bytes.
+    ///
+    /// Anything invalid is replaced with U+FFFD REPLACEMENT CHARACTER, which looks like this: �
+    ///
+    /// If `max` is provided, strings longer than `max` code units (not counting the terminator)
+    /// are rejected with [Error::LimitExceeded](enum.Error.html#variant.LimitExceeded)
+    /// after reading `max + 1` code units.
+    ///
+    /// *This is functionally identical to
+    /// [read_cstr_lossy](#method.read_cstr_lossy),
+    /// it's just a lot faster, but only works on readers that have `io::Seek`.*
+    fn read_cstr_lossy_fast<E: Encoding>(&mut self, max: Option<usize>) -> Result<String>
+    where
+        Self: io::Seek,
+    {
+        Ok(E::decode_lossy(&_null_chunk(self, max, E::UNIT_WIDTH)?))
+    }
 }
 
+#[cfg(feature = "alloc")]
 impl<R> ReadStrings for R where R: io::Read {}
+
+/// Provides methods for writing strings of various encodings.
+///
+/// All functions return bytes written, as all `io::Write` functions do.
+pub trait WriteStrings: io::Write {
+    /// Writes a string to the underlying writer as UTF-8 (without a length or null-terminator).
+    fn write_str_utf8(&mut self, s: &str) -> io::Result<usize> {
+        self.write_all(s.as_bytes()).map(|()| s.len())
+    }
+
+    /// Writes a string to the underlying writer as little-endian UTF-16
+    /// (without a length or null-terminator).
+    fn write_str_utf16_le(&mut self, s: &str) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        s.encode_utf16().try_fold(0, |n, unit| Ok(n + self.write_u16_le(unit)?))
+    }
+
+    /// Writes a string to the underlying writer as big-endian UTF-16
+    /// (without a length or null-terminator).
+    fn write_str_utf16_be(&mut self, s: &str) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        s.encode_utf16().try_fold(0, |n, unit| Ok(n + self.write_u16_be(unit)?))
+    }
+
+    /// Writes a string to the underlying writer as native-endian UTF-16
+    /// (without a length or null-terminator).
+    fn write_str_utf16_ne(&mut self, s: &str) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        s.encode_utf16().try_fold(0, |n, unit| Ok(n + self.write_u16_ne(unit)?))
+    }
+
+    /// Writes a string to the underlying writer as native-endian UTF-16
+    /// (without a length or null-terminator).
+    #[deprecated(note = "byte order is implicit, use `write_str_utf16_le`, `_be` or `_ne` instead")]
+    fn write_str_utf16(&mut self, s: &str) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        self.write_str_utf16_ne(s)
+    }
+
+    /// Writes a string to the underlying writer as UTF-8, followed by a null-terminator.
+    ///
+    /// If `max` is provided, strings longer than that many bytes (excluding the terminator)
+    /// are rejected with `io::ErrorKind::InvalidInput`, as are strings containing a null byte.
+    fn write_cstr_utf8(&mut self, s: &str, max: Option<usize>) -> io::Result<usize> {
+        if s.as_bytes().contains(&0x00) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "string contains a null byte",
+            ));
+        }
+        if let Some(max) = max {
+            if s.len() > max {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "string exceeds max length",
+                ));
+            }
+        }
+        self.write_all(s.as_bytes())?;
+        self.write_all(&[0x00]).map(|()| s.len() + 1)
+    }
+
+    _write_wide_impl!(
+        write_cstr_utf16_le, write_str_utf16_le, write_u16_le,
+        |s: &str| s.encode_utf16().count(), "little-endian UTF-16"
+    );
+    _write_wide_impl!(
+        write_cstr_utf16_be, write_str_utf16_be, write_u16_be,
+        |s: &str| s.encode_utf16().count(), "big-endian UTF-16"
+    );
+
+    /// Writes a string to the underlying writer as little-endian UTF-32
+    /// (without a length or null-terminator).
+    fn write_str_utf32_le(&mut self, s: &str) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        s.chars().try_fold(0, |n, c| Ok(n + self.write_u32_le(u32::from(c))?))
+    }
+
+    /// Writes a string to the underlying writer as big-endian UTF-32
+    /// (without a length or null-terminator).
+    fn write_str_utf32_be(&mut self, s: &str) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        s.chars().try_fold(0, |n, c| Ok(n + self.write_u32_be(u32::from(c))?))
+    }
+
+    _write_wide_impl!(
+        write_cstr_utf32_le, write_str_utf32_le, write_u32_le,
+        |s: &str| s.chars().count(), "little-endian UTF-32"
+    );
+    _write_wide_impl!(
+        write_cstr_utf32_be, write_str_utf32_be, write_u32_be,
+        |s: &str| s.chars().count(), "big-endian UTF-32"
+    );
+
+    /// Writes a string to the underlying writer as UTF-8 in a fixed-width field of `width`
+    /// bytes, filling the rest with null bytes or spaces as described by `padding`.
+    ///
+    /// Strings longer than `width` bytes are rejected with `io::ErrorKind::InvalidInput`,
+    /// as are strings containing a null byte. A string can fill the whole field,
+    /// in which case it has no terminator.
+    fn write_fixed_str_utf8(
+        &mut self,
+        s: &str,
+        width: usize,
+        padding: Padding,
+    ) -> io::Result<usize> {
+        if s.as_bytes().contains(&0x00) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "string contains a null byte",
+            ));
+        }
+        if s.len() > width {
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, "string exceeds field width"));
+        }
+        self.write_all(s.as_bytes())?;
+        let fill = match padding {
+            Padding::Null | Padding::Zeroes => 0x00,
+            Padding::Space => b' ',
+        };
+        _write_fill(self, fill, width - s.len()).map(|()| width)
+    }
+
+    /// Writes a string to the underlying writer as UTF-8, preceded by its length (in bytes)
+    /// stored as described by `prefix`.
+    ///
+    /// Strings longer than `max` (if provided) or than `prefix` can represent are rejected
+    /// with `io::ErrorKind::InvalidInput`.
+    fn write_pstr_utf8(
+        &mut self,
+        s: &str,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> io::Result<usize> {
+        let written = _write_prefix(&mut *self, prefix, s.len(), max)?;
+        Ok(written + self.write_str_utf8(s)?)
+    }
+
+    /// Writes a string to the underlying writer as little-endian UTF-16, preceded by its length
+    /// (in 16-bit integers, **NOT** bytes) stored as described by `prefix`.
+    ///
+    /// Strings longer than `max` (if provided) or than `prefix` can represent are rejected
+    /// with `io::ErrorKind::InvalidInput`.
+    fn write_pstr_utf16_le(
+        &mut self,
+        s: &str,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        let written = _write_prefix(&mut *self, prefix, s.encode_utf16().count(), max)?;
+        Ok(written + self.write_str_utf16_le(s)?)
+    }
+
+    /// Writes a string to the underlying writer as big-endian UTF-16, preceded by its length
+    /// (in 16-bit integers, **NOT** bytes) stored as described by `prefix`.
+    ///
+    /// Strings longer than `max` (if provided) or than `prefix` can represent are rejected
+    /// with `io::ErrorKind::InvalidInput`.
+    fn write_pstr_utf16_be(
+        &mut self,
+        s: &str,
+        prefix: LengthPrefix,
+        max: Option<usize>,
+    ) -> io::Result<usize>
+    where
+        Self: WritePrimitives,
+    {
+        let written = _write_prefix(&mut *self, prefix, s.encode_utf16().count(), max)?;
+        Ok(written + self.write_str_utf16_be(s)?)
+    }
+
+    _write_codepage_impl!(
+        write_str_latin1, write_cstr_latin1,
+        codepage::latin1_encode, "Latin-1 (ISO 8859-1)"
+    );
+    _write_codepage_impl!(
+        write_str_cp1252, write_cstr_cp1252,
+        codepage::cp1252_encode, "Windows-1252"
+    );
+    _write_codepage_impl!(
+        write_str_cp437, write_cstr_cp437,
+        codepage::cp437_encode, "code page 437"
+    );
+
+    #[cfg(feature = "cjk")]
+    _write_multibyte_impl!(
+        write_str_shift_jis, write_cstr_shift_jis,
+        encoding_rs::SHIFT_JIS, "Shift-JIS"
+    );
+    #[cfg(feature = "cjk")]
+    _write_multibyte_impl!(write_str_gbk, write_cstr_gbk, encoding_rs::GBK, "GBK");
+    #[cfg(feature = "cjk")]
+    _write_multibyte_impl!(write_str_euc_kr, write_cstr_euc_kr, encoding_rs::EUC_KR, "EUC-KR");
+
+    /// Writes a string to the underlying writer in the encoding `E`
+    /// (without a length or null-terminator).
+    ///
+    /// Strings `E` can't represent are rejected, before anything is written, with the
+    /// `io::Error` converted from the [Error](enum.Error.html) `E::encode` returned.
+    #[cfg(feature = "alloc")]
+    fn write_str<E: Encoding>(&mut self, s: &str) -> io::Result<usize> {
+        let buf = E::encode(s)?;
+        self.write_all(&buf).map(|()| buf.len())
+    }
+
+    /// Writes a string to the underlying writer in the encoding `E`,
+    /// followed by a null-terminator of `E::UNIT_WIDTH` zero bytes.
+    ///
+    /// Strings `E` can't represent are rejected, before anything is written, with the
+    /// `io::Error` converted from the [Error](enum.Error.html) `E::encode` returned.
+    /// So are strings longer than `max` code units (excluding the terminator),
+    /// if it's provided, and strings containing a null character.
+    #[cfg(feature = "alloc")]
+    fn write_cstr<E: Encoding>(&mut self, s: &str, max: Option<usize>) -> io::Result<usize> {
+        if s.contains('\0') {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "string contains a null byte",
+            ));
+        }
+        let mut buf = E::encode(s)?;
+        if max.is_some_and(|max| buf.len() / E::UNIT_WIDTH > max) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "string exceeds max length",
+            ));
+        }
+        buf.resize(buf.len() + E::UNIT_WIDTH, 0x00);
+        self.write_all(&buf).map(|()| buf.len())
+    }
+}
+
+impl<W> WriteStrings for W where W: io::Write {}
+
+/// Types which can be read from a reader, usually implemented with `#[derive(MinioRead)]`
+/// (requires the `derive` feature).
+pub trait MinioRead: Sized {
+    /// Reads an instance of this type from the given reader.
+    fn minio_read<R: io::Read>(rdr: &mut R) -> io::Result<Self>;
+}
+
+/// Types which can be written to a writer, usually implemented with `#[derive(MinioWrite)]`
+/// (requires the `derive` feature).
+pub trait MinioWrite {
+    /// Writes this value to the given writer, returning bytes written.
+    fn minio_write<W: io::Write>(&self, wtr: &mut W) -> io::Result<usize>;
+}
+
+/// Types which always take the same number of bytes when read or written, usually implemented
+/// with `#[derive(FixedSize)]` (requires the `derive` feature).
+pub trait FixedSize {
+    /// Size in bytes.
+    const SIZE: usize;
+}
+
+macro_rules! _fixed_size_impl {
+    ($($t: ty),*) => {
+        $(impl FixedSize for $t {
+            const SIZE: usize = size_of::<$t>();
+        })*
+    };
+}
+
+_fixed_size_impl!(u8, i8, u16, i16, u32, i32, u64, i64, u128, i128, f32, f64);
+
+impl<T, const N: usize> FixedSize for [T; N]
+where
+    T: FixedSize,
+{
+    const SIZE: usize = T::SIZE * N;
+}
+
+#[cfg(feature = "derive")]
+pub use minio_derive::{FixedSize, MinioRead, MinioWrite};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::io;
+    #[cfg(feature = "alloc")]
+    pub use alloc::vec::Vec;
+}
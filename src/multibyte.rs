@@ -0,0 +1,54 @@
+use crate::{Error, Result};
+use alloc::{string::String, vec::Vec};
+use encoding_rs::{DecoderResult, EncoderResult, Encoding};
+
+// Multi-byte legacy encodings, with the tables coming from `encoding_rs`.
+//
+// Shift-JIS, GBK and EUC-KR all use 0x00 only for U+0000: it's never the second byte of
+// a character, so the first null byte always ends a null-terminated string, even right after
+// a lead byte (which then just makes for a truncated character).
+
+/// Decodes `bytes`, failing with the offset of the first malformed sequence.
+pub(crate) fn decode(encoding: &'static Encoding, bytes: &[u8]) -> Result<String> {
+    let mut decoder = encoding.new_decoder_without_bom_handling();
+    let mut s = String::with_capacity(bytes.len());
+    let mut read = 0;
+    loop {
+        let (result, n) =
+            decoder.decode_to_string_without_replacement(&bytes[read..], &mut s, true);
+        read += n;
+        match result {
+            DecoderResult::InputEmpty => break Ok(s),
+            DecoderResult::OutputFull => s.reserve(s.capacity().max(4)),
+            DecoderResult::Malformed(len, after) => {
+                let offset = read - usize::from(len) - usize::from(after);
+                break Err(Error::InvalidEncoding { string_offset: Some(offset as u64) });
+            },
+        }
+    }
+}
+
+/// Decodes `bytes`, replacing malformed sequences with U+FFFD REPLACEMENT CHARACTER.
+pub(crate) fn decode_lossy(encoding: &'static Encoding, bytes: &[u8]) -> String {
+    encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+/// Encodes `s`, failing with the first character that can't be represented
+/// and where in the output it would have gone.
+pub(crate) fn encode(encoding: &'static Encoding, s: &str) -> Result<Vec<u8>> {
+    let mut encoder = encoding.new_encoder();
+    let mut buf = Vec::with_capacity(s.len());
+    let mut read = 0;
+    loop {
+        let (result, n) =
+            encoder.encode_from_utf8_to_vec_without_replacement(&s[read..], &mut buf, true);
+        read += n;
+        match result {
+            EncoderResult::InputEmpty => break Ok(buf),
+            EncoderResult::OutputFull => buf.reserve(buf.capacity().max(4)),
+            EncoderResult::Unmappable(ch) => {
+                break Err(Error::Unencodable { ch, string_offset: Some(buf.len() as u64) });
+            },
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use crate::io;
+use alloc::vec::Vec;
+use core::mem::size_of;
+
+macro_rules! _peek_impl {
+    ($t: ty, $name: literal, $le: ident, $be: ident) => {
+        #[inline]
+        #[doc = "Returns the next "] #[doc = $name] #[doc = "(little-endian) without consuming it."]
+        pub fn $le(&mut self) -> io::Result<$t> {
+            let mut buf = [0u8; size_of::<$t>()];
+            buf.copy_from_slice(self.peek_bytes(size_of::<$t>())?);
+            Ok(<$t>::from_le_bytes(buf))
+        }
+
+        #[inline]
+        #[doc = "Returns the next "] #[doc = $name] #[doc = "(big-endian) without consuming it."]
+        pub fn $be(&mut self) -> io::Result<$t> {
+            let mut buf = [0u8; size_of::<$t>()];
+            buf.copy_from_slice(self.peek_bytes(size_of::<$t>())?);
+            Ok(<$t>::from_be_bytes(buf))
+        }
+    };
+}
+
+/// Wraps a reader with a lookahead buffer, so upcoming bytes can be looked at before deciding
+/// how to read them, without needing `io::Seek`.
+///
+/// Peeked bytes are kept until they're read, so this implements `io::Read`
+/// and everything else in minio works on it as usual. It also implements `io::BufRead`,
+/// which reads ahead by up to 8 KiB whenever the buffer runs out.
+#[derive(Clone, Debug)]
+pub struct Peekable<R> {
+    inner: R,
+    buf: Vec<u8>,
+    pos: usize,
+}
+
+#[rustfmt::skip]
+impl<R> Peekable<R>
+where
+    R: io::Read,
+{
+    _peek_impl!(u16, "`u16`", peek_u16_le, peek_u16_be);
+    _peek_impl!(u32, "`u32`", peek_u32_le, peek_u32_be);
+    _peek_impl!(u64, "`u64`", peek_u64_le, peek_u64_be);
+}
+
+impl<R> Peekable<R>
+where
+    R: io::Read,
+{
+    /// Creates a new peekable reader over `inner`, with nothing buffered yet.
+    pub fn new(inner: R) -> Self {
+        Self { inner, buf: Vec::new(), pos: 0 }
+    }
+
+    /// Returns the next `n` bytes without consuming them,
+    /// reading more from the underlying reader if needed.
+    ///
+    /// If it runs out first, this fails with `io::ErrorKind::UnexpectedEof`,
+    /// but whatever it did get is still there to be read.
+    pub fn peek_bytes(&mut self, n: usize) -> io::Result<&[u8]> {
+        if self.buf.len() - self.pos < n {
+            self.buf.drain(..self.pos);
+            self.pos = 0;
+            let mut len = self.buf.len();
+            self.buf.resize(n, 0);
+            while len < n {
+                match self.inner.read(&mut self.buf[len..]) {
+                    Ok(0) => {
+                        self.buf.truncate(len);
+                        return Err(io::ErrorKind::UnexpectedEof.into());
+                    },
+                    Ok(read) => len += read,
+                    Err(e) if e.kind() == io::ErrorKind::Interrupted => {},
+                    Err(e) => {
+                        self.buf.truncate(len);
+                        return Err(e);
+                    },
+                }
+            }
+        }
+        Ok(&self.buf[self.pos..self.pos + n])
+    }
+
+    /// Returns the next byte without consuming it.
+    #[inline]
+    pub fn peek_u8(&mut self) -> io::Result<u8> {
+        self.peek_bytes(1).map(|x| x[0])
+    }
+
+    /// Returns the bytes which have been peeked at but not read yet.
+    pub fn buffer(&self) -> &[u8] {
+        &self.buf[self.pos..]
+    }
+
+    /// Returns a reference to the underlying reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader.
+    ///
+    /// Reading from this skips past anything that's buffered.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader, losing anything that's buffered
+    /// (see [buffer](#method.buffer)).
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R> io::Read for Peekable<R>
+where
+    R: io::Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let buffered = &self.buf[self.pos..];
+        if buffered.is_empty() {
+            return self.inner.read(buf);
+        }
+        let n = buffered.len().min(buf.len());
+        buf[..n].copy_from_slice(&buffered[..n]);
+        self.pos += n;
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+        }
+        Ok(n)
+    }
+}
+
+impl<R> io::BufRead for Peekable<R>
+where
+    R: io::Read,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+            self.buf.resize(8192, 0);
+            match self.inner.read(&mut self.buf) {
+                Ok(n) => self.buf.truncate(n),
+                Err(e) => {
+                    self.buf.clear();
+                    return Err(e);
+                },
+            }
+        }
+        Ok(&self.buf[self.pos..])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.pos = (self.pos + amt).min(self.buf.len());
+        if self.pos == self.buf.len() {
+            self.buf.clear();
+            self.pos = 0;
+        }
+    }
+}
@@ -1,8 +1,9 @@
-use crate::{ReadPrimitives, ReadStrings, WritePrimitives};
+use crate::{ReadPrimitives, ReadStrings, WritePrimitives, WriteStrings};
 use std::mem::size_of;
 
 #[test]
 #[rustfmt::skip]
+#[allow(const_item_mutation)] // every read starts from a fresh copy of `TEST`
 fn read_write_primitives() {
     const TEST: &[u8] = &[
         0xCD, 0xCC, 0xDC, 0x40,
@@ -67,7 +68,8 @@ fn read_write_primitives() {
 
 #[test]
 fn read_write_strings() {
-    use std::{io::Cursor, slice};
+    use crate::Error;
+    use std::io::Cursor;
 
     let test_utf8 = "ℍ𝕖𝕝𝕝𝕠, 𝕨𝕠𝕣𝕝𝕕! 👋";
     let test_utf8_invalid = b"Hello, \x80world!";
@@ -80,16 +82,13 @@ fn read_write_strings() {
         Cursor::new(test_utf8.as_bytes())
             .read_str_utf8(test_utf8.len())
             .unwrap()
-            .unwrap()
             .as_str()
     );
 
-    assert!(
-        Cursor::new(test_utf8_invalid)
-            .read_str_utf8(test_utf8_invalid.len())
-            .unwrap()
-            .is_err()
-    );
+    assert!(matches!(
+        Cursor::new(test_utf8_invalid).read_str_utf8(test_utf8_invalid.len()),
+        Err(Error::InvalidUtf8 { string_offset: Some(7) }),
+    ));
     assert_eq!(
         "Hello, �world!",
         Cursor::new(test_utf8_invalid)
@@ -98,27 +97,43 @@ fn read_write_strings() {
             .as_str()
     );
 
-    let utf16_bytes = test_utf8
-        .encode_utf16()
-        .collect::<Vec<_>>()
-        .into_boxed_slice();
+    let utf16_units = test_utf8.encode_utf16().collect::<Vec<_>>();
+    let utf16_le = utf16_units.iter().flat_map(|x| x.to_le_bytes()).collect::<Vec<_>>();
+    let utf16_be = utf16_units.iter().flat_map(|x| x.to_be_bytes()).collect::<Vec<_>>();
+    assert_eq!(
+        test_utf8,
+        Cursor::new(&utf16_le)
+            .read_str_utf16_le(utf16_units.len())
+            .unwrap()
+            .as_str()
+    );
     assert_eq!(
         test_utf8,
-        Cursor::new(unsafe {
-            slice::from_raw_parts(utf16_bytes.as_ptr() as *const u8, utf16_bytes.len() * 2)
-        })
-        .read_str_utf16(utf16_bytes.len())
-        .unwrap()
-        .unwrap()
-        .as_str()
+        Cursor::new(&utf16_be)
+            .read_str_utf16_be(utf16_units.len())
+            .unwrap()
+            .as_str()
     );
 
+    // odd offset into the buffer, which would be misaligned for u16
+    let mut utf16_odd = vec![0xFF];
+    utf16_odd.extend_from_slice(&utf16_le);
+    let mut rdr = Cursor::new(&utf16_odd);
+    rdr.read_u8().unwrap();
+    assert_eq!(test_utf8, rdr.read_str_utf16_lossy_le(utf16_units.len()).unwrap().as_str());
+
+    // unpaired surrogate
+    assert!(matches!(
+        Cursor::new(b"A\x00\x00\xD8").read_str_utf16_le(2),
+        Err(Error::InvalidUtf16 { string_offset: Some(2) }),
+    ));
+    assert_eq!("�", Cursor::new(b"\xD8\x00").read_str_utf16_lossy_be(1).unwrap().as_str());
+
     assert_eq!(
         "Hello, world!",
         Cursor::new(test_cstring)
             .read_cstr_utf8(None)
             .unwrap()
-            .unwrap()
             .as_str()
     );
 
@@ -136,10 +151,1178 @@ fn read_write_strings() {
         Cursor::new(test_cstring)
             .read_cstr_utf8_fast(None)
             .unwrap()
-            .unwrap()
             .as_bytes()
     );
 
     // writing
-    // ...... oh that doesn't exist yet!
+    let mut buf = vec![];
+    assert_eq!(test_utf8.len(), buf.write_str_utf8(test_utf8).unwrap());
+    assert_eq!(test_utf8.as_bytes(), &*buf);
+    buf.clear();
+
+    assert_eq!(utf16_le.len(), buf.write_str_utf16_le(test_utf8).unwrap());
+    assert_eq!(utf16_le, buf);
+    buf.clear();
+
+    assert_eq!(utf16_be.len(), buf.write_str_utf16_be(test_utf8).unwrap());
+    assert_eq!(utf16_be, buf);
+    buf.clear();
+
+    assert_eq!(
+        test_cstring.len(),
+        buf.write_cstr_utf8("Hello, world!", None).unwrap()
+    );
+    assert_eq!(&test_cstring[..], &*buf);
+    buf.clear();
+
+    // interior null, too long for max
+    assert!(buf.write_cstr_utf8("Hello,\0world!", None).is_err());
+    assert!(buf.write_cstr_utf8("Hello, world!", Some(4)).is_err());
+    assert!(buf.write_cstr_utf8("Hello, world!", Some(13)).is_ok());
+}
+
+#[test]
+fn read_write_pstrings() {
+    use crate::LengthPrefix;
+    use std::io::Cursor;
+
+    let prefixes = [
+        LengthPrefix::U8,
+        LengthPrefix::U16Le,
+        LengthPrefix::U16Be,
+        LengthPrefix::U32Le,
+        LengthPrefix::U32Be,
+        LengthPrefix::Uleb128,
+    ];
+    let test_utf8 = "ℍ𝕖𝕝𝕝𝕠, 𝕨𝕠𝕣𝕝𝕕! 👋";
+
+    for &prefix in prefixes.iter() {
+        let mut buf = vec![];
+        let written = buf.write_pstr_utf8(test_utf8, prefix, None).unwrap();
+        assert_eq!(written, buf.len());
+        let mut rdr = Cursor::new(&buf);
+        assert_eq!(test_utf8, rdr.read_pstr_utf8(prefix, None).unwrap());
+        assert_eq!(written as u64, rdr.position());
+
+        buf.clear();
+        let written = buf.write_pstr_utf16_be(test_utf8, prefix, None).unwrap();
+        assert_eq!(written, buf.len());
+        assert_eq!(
+            test_utf8,
+            Cursor::new(&buf).read_pstr_utf16_lossy_be(prefix, None).unwrap()
+        );
+
+        // over max, on both ends
+        assert!(buf.write_pstr_utf8(test_utf8, prefix, Some(4)).is_err());
+        buf.clear();
+        buf.write_pstr_utf8(test_utf8, prefix, None).unwrap();
+        assert!(Cursor::new(&buf).read_pstr_utf8(prefix, Some(4)).is_err());
+    }
+
+    // byte layout
+    let mut buf = vec![];
+    buf.write_pstr_utf8("Hi", LengthPrefix::U16Be, None).unwrap();
+    assert_eq!(b"\x00\x02Hi", &*buf);
+    buf.clear();
+    buf.write_pstr_utf16_le(&"a".repeat(300), LengthPrefix::Uleb128, None).unwrap();
+    assert_eq!(b"\xAC\x02a\x00", &buf[..4]);
+
+    // too long for the prefix type
+    assert!(buf.write_pstr_utf8(&"a".repeat(256), LengthPrefix::U8, None).is_err());
+
+    // overlong LEB128 prefix
+    let overflow = b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\x02";
+    assert!(Cursor::new(overflow).read_pstr_utf8_lossy(LengthPrefix::Uleb128, None).is_err());
+}
+
+#[test]
+fn read_write_leb128() {
+    use crate::{zigzag_decode, zigzag_encode};
+    use std::io::Cursor;
+
+    // (value, encoding) pairs, mostly from the DWARF spec
+    let unsigned: &[(u64, &[u8])] = &[
+        (0, b"\x00"),
+        (2, b"\x02"),
+        (127, b"\x7F"),
+        (128, b"\x80\x01"),
+        (12857, b"\xB9\x64"),
+        (624485, b"\xE5\x8E\x26"),
+        (u64::MAX, b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\x01"),
+    ];
+    let signed: &[(i64, &[u8])] = &[
+        (0, b"\x00"),
+        (2, b"\x02"),
+        (-2, b"\x7E"),
+        (127, b"\xFF\x00"),
+        (-127, b"\x81\x7F"),
+        (-128, b"\x80\x7F"),
+        (-123456, b"\xC0\xBB\x78"),
+        (i64::MAX, b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\x00"),
+        (i64::MIN, b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x7F"),
+    ];
+
+    let mut buf = vec![];
+    for &(value, encoded) in unsigned {
+        assert_eq!(value, { encoded }.read_uleb128_u64().unwrap());
+        assert_eq!(encoded.len(), buf.write_uleb128_u64(value).unwrap());
+        assert_eq!(encoded, &*buf);
+        buf.clear();
+    }
+    for &(value, encoded) in signed {
+        assert_eq!(value, { encoded }.read_sleb128_i64().unwrap());
+        assert_eq!(encoded.len(), buf.write_sleb128_i64(value).unwrap());
+        assert_eq!(encoded, &*buf);
+        buf.clear();
+    }
+
+    // padded encodings are only fine when asked for, overflowing ones never are
+    let padded: &[(i64, &[u8])] = &[
+        (2, b"\x82\x80\x00"),
+        (0, b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x00"),
+        (-2, b"\xFE\x7F"),
+        (63, b"\xBF\x00"),
+    ];
+    for &(value, encoded) in padded {
+        if value >= 0 {
+            let err = { encoded }.read_uleb128_u64().unwrap_err();
+            assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+            assert_eq!(value as u64, { encoded }.read_uleb128_u64_lenient().unwrap());
+        }
+        let err = { encoded }.read_sleb128_i64().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        assert_eq!(value, { encoded }.read_sleb128_i64_lenient().unwrap());
+    }
+    // ...but a sign byte that changes the sign isn't padding
+    assert_eq!(64, { &b"\xC0\x00"[..] }.read_sleb128_i64().unwrap());
+    assert_eq!(-65, { &b"\xBF\x7F"[..] }.read_sleb128_i64().unwrap());
+    let overflow: &[&[u8]] = &[
+        b"\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\xFF\x02",
+        b"\x80\x80\x80\x80\x80\x80\x80\x80\x80\x81\x00",
+    ];
+    for &encoded in overflow {
+        let err = { encoded }.read_uleb128_u64().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+        let err = { encoded }.read_sleb128_i64().unwrap_err();
+        assert_eq!(std::io::ErrorKind::InvalidData, err.kind());
+    }
+    assert!(Cursor::new(b"\x80").read_uleb128_u64().is_err());
+
+    // zigzag
+    for &(value, zigzag) in &[(0i64, 0u64), (-1, 1), (1, 2), (-2, 3), (i64::MAX, u64::MAX - 1)] {
+        assert_eq!(zigzag, zigzag_encode(value));
+        assert_eq!(value, zigzag_decode(zigzag));
+    }
+    assert_eq!(i64::MIN, zigzag_decode(u64::MAX));
+    buf.write_zigzag_i64(-65).unwrap();
+    assert_eq!(b"\x81\x01", &*buf);
+    assert_eq!(-65, Cursor::new(&buf).read_zigzag_i64().unwrap());
+}
+
+#[test]
+fn read_write_bits() {
+    use crate::{BitOrder, BitReader, BitWriter};
+
+    // 3 bits (0b101), 7 bits (0b0011100), signed 4 bits (-3 = 0b1101), 2 bits (0b01),
+    // then a byte-aligned u16
+    let msb: &[u8] = &[0b1010_0111, 0b0011_0101, 0x12, 0x34];
+    let lsb: &[u8] = &[0b1110_0101, 0b1011_0100, 0x12, 0x34];
+
+    for &(order, bytes) in &[(BitOrder::MsbFirst, msb), (BitOrder::LsbFirst, lsb)] {
+        let mut rdr = BitReader::new(bytes, order);
+        assert_eq!(0b101, rdr.read_bits(3).unwrap());
+        assert_eq!(0b0011100, rdr.read_bits(7).unwrap());
+        assert_eq!(-3, rdr.read_bits_signed(4).unwrap());
+        assert!(!rdr.is_aligned());
+        assert!(!rdr.read_bit().unwrap());
+        assert!(rdr.read_bit().unwrap());
+        assert!(rdr.is_aligned());
+        assert_eq!(0x1234, rdr.aligned().read_u16_be().unwrap());
+        assert!(rdr.read_bit().is_err());
+
+        let mut wtr = BitWriter::new(vec![], order);
+        wtr.write_bits(3, 0b101).unwrap();
+        wtr.write_bits(7, 0b0011100).unwrap();
+        wtr.write_bits_signed(4, -3).unwrap();
+        wtr.write_bit(false).unwrap();
+        wtr.write_bit(true).unwrap();
+        wtr.aligned().unwrap().write_u16_be(0x1234).unwrap();
+        assert!(wtr.write_bits(2, 4).is_err());
+        assert!(wtr.write_bits_signed(4, 8).is_err());
+        assert!(wtr.write_bits_signed(4, -9).is_err());
+        assert_eq!(bytes, &*wtr.into_inner().unwrap());
+    }
+
+    // full width, and partial bytes being dropped or padded
+    let mut wtr = BitWriter::new(vec![], BitOrder::LsbFirst);
+    wtr.write_bits(64, u64::MAX - 1).unwrap();
+    wtr.write_bits_signed(64, i64::MIN).unwrap();
+    wtr.write_bit(true).unwrap();
+    let buf = wtr.into_inner().unwrap();
+    assert_eq!(17, buf.len());
+    let mut rdr = BitReader::new(&buf[..], BitOrder::LsbFirst);
+    assert_eq!(u64::MAX - 1, rdr.read_bits(64).unwrap());
+    assert_eq!(i64::MIN, rdr.read_bits_signed(64).unwrap());
+    assert!(rdr.read_bit().unwrap());
+    assert!(!rdr.read_bit().unwrap());
+    rdr.align_to_byte();
+    assert!(rdr.read_bit().is_err());
+}
+
+#[test]
+fn byte_cursor() {
+    use crate::ByteCursor;
+
+    let data = b"\xCD\xCC\xDC\x40\x01\x02Hello\0\x80world\0rest";
+    let mut cur = ByteCursor::new(data);
+    assert_eq!(Some(6.9), cur.read_f32_le());
+    assert_eq!(Some(0x0102), cur.read_u16_be());
+    assert_eq!(6, cur.position());
+
+    // max counts bytes before the terminator
+    assert_eq!(None, cur.read_cstr_utf8(Some(4)));
+    assert_eq!(6, cur.position());
+    let hello = cur.read_cstr_utf8(Some(5)).unwrap().unwrap();
+    assert_eq!("Hello", hello);
+    assert_eq!(data[6..].as_ptr(), hello.as_ptr());
+    assert_eq!("�world", cur.read_cstr_utf8_lossy(None).unwrap());
+
+    // no terminator
+    assert_eq!(None, cur.read_cstr(None));
+    assert_eq!(Some("re"), cur.read_str_utf8(2).map(Result::unwrap));
+    assert_eq!(None, cur.read_u32_le());
+    assert_eq!(Some(&b"st"[..]), cur.read_bytes(2));
+    assert!(cur.is_empty());
+    assert_eq!(None, cur.read_u8());
+
+    cur.set_position(data.len() + 1);
+    assert_eq!(None, cur.read_u8());
+    cur.set_position(0);
+    assert_eq!(Some(205), cur.read_u8());
+}
+
+#[test]
+fn read_write_slices() {
+    // long enough to span several chunks of the write buffer
+    let values = (0..3000u32).map(|x| x.wrapping_mul(0x9E3779B9)).collect::<Vec<_>>();
+    let floats = values.iter().map(|&x| x as f32 / 7.0).collect::<Vec<_>>();
+
+    let mut one_by_one = vec![];
+    let mut bulk = vec![];
+    for &x in &values {
+        one_by_one.write_u32_be(x).unwrap();
+    }
+    assert_eq!(values.len() * 4, bulk.write_u32_slice_be(&values).unwrap());
+    assert_eq!(one_by_one, bulk);
+
+    let mut read = vec![0u32; values.len()];
+    (&bulk[..]).read_u32_into_be(&mut read).unwrap();
+    assert_eq!(values, read);
+    (&bulk[..]).read_u32_into_le(&mut read).unwrap();
+    assert!(values.iter().zip(&read).all(|(a, b)| a.swap_bytes() == *b));
+
+    one_by_one.clear();
+    bulk.clear();
+    for &x in &floats {
+        one_by_one.write_f32_le(x).unwrap();
+    }
+    bulk.write_f32_slice_le(&floats).unwrap();
+    assert_eq!(one_by_one, bulk);
+    let mut read = vec![0.0f32; floats.len()];
+    (&bulk[..]).read_f32_into_le(&mut read).unwrap();
+    assert_eq!(floats, read);
+
+    // unaligned source, native order and single bytes
+    let mut odd = vec![0xFF];
+    odd.write_i16_slice_ne(&[-2, 300]).unwrap();
+    odd.write_i8_slice(&[-1, 1]).unwrap();
+    let mut rdr = &odd[1..];
+    let mut shorts = [0i16; 2];
+    let mut bytes = [0i8; 2];
+    rdr.read_i16_into_ne(&mut shorts).unwrap();
+    rdr.read_i8_into(&mut bytes).unwrap();
+    assert_eq!([-2, 300], shorts);
+    assert_eq!([-1, 1], bytes);
+
+    // not enough data
+    assert!((&odd[..]).read_u64_into_le(&mut [0; 2]).is_err());
+}
+
+#[test]
+fn generic_byte_order() {
+    use crate::{BigEndian, ByteOrder, LittleEndian, NativeEndian};
+
+    // the same parser for both variants of a format
+    fn header<E: ByteOrder>(mut rdr: &[u8]) -> (u16, i32, f64) {
+        (
+            rdr.read_u16::<E>().unwrap(),
+            rdr.read_i32::<E>().unwrap(),
+            rdr.read_f64::<E>().unwrap(),
+        )
+    }
+    fn write_header<E: ByteOrder>(buf: &mut Vec<u8>, val: (u16, i32, f64)) {
+        buf.write_u16::<E>(val.0).unwrap();
+        buf.write_i32::<E>(val.1).unwrap();
+        buf.write_f64::<E>(val.2).unwrap();
+    }
+
+    let val = (0x1234, -2, 6.9);
+    let (mut le, mut be, mut ne) = (vec![], vec![], vec![]);
+    write_header::<LittleEndian>(&mut le, val);
+    write_header::<BigEndian>(&mut be, val);
+    write_header::<NativeEndian>(&mut ne, val);
+    assert_eq!(&le[..6], b"\x34\x12\xFE\xFF\xFF\xFF");
+    assert_eq!(&be[..6], b"\x12\x34\xFF\xFF\xFF\xFE");
+    assert_eq!(&ne, if cfg!(target_endian = "little") { &le } else { &be });
+    assert_eq!(val, header::<LittleEndian>(&le));
+    assert_eq!(val, header::<BigEndian>(&be));
+    assert_eq!((&le[..]).read_u16_le().unwrap(), (&le[..]).read_u16::<LittleEndian>().unwrap());
+    assert_eq!(0x1234u128.swap_bytes(), BigEndian::from_bytes(LittleEndian::to_bytes(0x1234u128)));
+}
+
+#[test]
+fn runtime_endian() {
+    use crate::{Endian, EndianReader};
+
+    // TIFF-style header: byte order marker, then numbers in that order
+    fn parse(data: &[u8]) -> (u16, u32, f32) {
+        let endian = match &data[..2] {
+            b"II" => Endian::Little,
+            b"MM" => Endian::Big,
+            _ => panic!("bad marker"),
+        };
+        let mut rdr = EndianReader::new(&data[2..], endian);
+        (rdr.read_u16().unwrap(), rdr.read_u32().unwrap(), rdr.read_f32().unwrap())
+    }
+
+    let val = (42, 8, 6.9);
+    for &(endian, marker) in &[(Endian::Little, b"II"), (Endian::Big, b"MM")] {
+        let mut buf = marker.to_vec();
+        buf.write_u16_with(endian, val.0).unwrap();
+        buf.write_u32_with(endian, val.1).unwrap();
+        buf.write_f32_with(endian, val.2).unwrap();
+        assert_eq!(val, parse(&buf));
+        assert_eq!(42, (&buf[2..]).read_u16_with(endian).unwrap());
+    }
+
+    let mut native = vec![];
+    native.write_i64_with(Endian::NATIVE, -2).unwrap();
+    assert_eq!(-2, (&native[..]).read_i64_ne().unwrap());
+
+    // the wrapper still works with everything else
+    let mut rdr = EndianReader::new(&b"\x01\x00\x01hi\0"[..], Endian::Big);
+    assert_eq!(1, rdr.read_u8().unwrap());
+    rdr.set_endian(Endian::Little);
+    assert_eq!(0x0100, rdr.read_u16().unwrap());
+    assert_eq!("hi", rdr.read_cstr_utf8(None).unwrap());
+}
+
+#[test]
+fn read_write_half() {
+    use crate::{bf16_to_f32, f16_to_f32, f32_to_bf16, f32_to_f16, LittleEndian};
+
+    assert_eq!(1.0, f16_to_f32(0x3C00));
+    assert_eq!(-2.0, f16_to_f32(0xC000));
+    assert_eq!(65504.0, f16_to_f32(0x7BFF));
+    assert_eq!(2f32.powi(-24), f16_to_f32(0x0001));
+    assert_eq!(2f32.powi(-14) - 2f32.powi(-24), f16_to_f32(0x03FF));
+    assert_eq!(f32::INFINITY, f16_to_f32(0x7C00));
+    assert_eq!(f32::NEG_INFINITY, f16_to_f32(0xFC00));
+    assert!(f16_to_f32(0x7E00).is_nan());
+    assert_eq!((-0f32).to_bits(), f16_to_f32(0x8000).to_bits());
+
+    assert_eq!(0x3C00, f32_to_f16(1.0));
+    assert_eq!(0x7BFF, f32_to_f16(65504.0));
+    assert_eq!(0x0001, f32_to_f16(2f32.powi(-24)));
+    assert_eq!(0x7C00, f32_to_f16(f32::INFINITY));
+    assert_eq!(0x7E00, f32_to_f16(f32::NAN) & 0x7E00);
+    // ties to even: 1 + 2^-11 is halfway between 0x3C00 and 0x3C01
+    assert_eq!(0x3C00, f32_to_f16(1.0 + 2f32.powi(-11)));
+    assert_eq!(0x3C02, f32_to_f16(1.0 + 3.0 * 2f32.powi(-11)));
+    assert_eq!(0x3C01, f32_to_f16(1.0 + 2f32.powi(-11) + 2f32.powi(-20)));
+    // overflow and underflow, including rounding up into the smallest subnormal
+    assert_eq!(0x7C00, f32_to_f16(65520.0));
+    assert_eq!(0x7BFF, f32_to_f16(65519.0));
+    assert_eq!(0x8000, f32_to_f16(-1e-30));
+    assert_eq!(0x0000, f32_to_f16(2f32.powi(-25)));
+    assert_eq!(0x0001, f32_to_f16(2f32.powi(-25) * 1.5));
+    assert_eq!(0x0400, f32_to_f16(2f32.powi(-14) - 2f32.powi(-26)));
+
+    // every half round-trips exactly through f32
+    for bits in 0..=u16::MAX {
+        let val = f16_to_f32(bits);
+        if val.is_nan() {
+            assert!(f16_to_f32(f32_to_f16(val)).is_nan());
+        } else {
+            assert_eq!(bits, f32_to_f16(val), "{:#06x}", bits);
+        }
+        let val = bf16_to_f32(bits);
+        if val.is_nan() {
+            assert!(bf16_to_f32(f32_to_bf16(val)).is_nan());
+        } else {
+            assert_eq!(bits, f32_to_bf16(val), "{:#06x}", bits);
+        }
+    }
+
+    assert_eq!(0x3F80, f32_to_bf16(1.0));
+    assert_eq!(0x3F80, f32_to_bf16(f32::from_bits(0x3F80_8000)));
+    assert_eq!(0x3F82, f32_to_bf16(f32::from_bits(0x3F81_8000)));
+    assert_eq!(0x3F81, f32_to_bf16(f32::from_bits(0x3F80_8001)));
+    assert_eq!(0x7F80, f32_to_bf16(f32::MAX));
+    assert!(bf16_to_f32(f32_to_bf16(f32::from_bits(0x7F80_0001))).is_nan());
+
+    let mut buf = vec![];
+    assert_eq!(2, buf.write_f16_le(1.0).unwrap());
+    assert_eq!(2, buf.write_f16_be(-2.0).unwrap());
+    assert_eq!(2, buf.write_bf16_le(1.0).unwrap());
+    assert_eq!(2, buf.write_bf16_be(0.5).unwrap());
+    assert_eq!(buf, [0x00, 0x3C, 0xC0, 0x00, 0x80, 0x3F, 0x3F, 0x00]);
+    let mut rdr = &buf[..];
+    assert_eq!(1.0, rdr.read_f16::<LittleEndian>().unwrap());
+    assert_eq!(-2.0, rdr.read_f16_be().unwrap());
+    assert_eq!(1.0, rdr.read_bf16_le().unwrap());
+    assert_eq!(0.5, rdr.read_bf16_be().unwrap());
+    assert!(rdr.read_f16_ne().is_err());
+
+    // bulk, spanning several chunks of the internal buffer
+    let src = (0..5000).map(|x| (x % 2048) as f32 - 1024.0).collect::<Vec<_>>();
+    let mut buf = vec![];
+    assert_eq!(10000, buf.write_f16_slice_be(&src).unwrap());
+    assert_eq!(10, buf.write_bf16_slice_ne(&[0.5, -3.0, 256.0, 1e30, 0.1]).unwrap());
+    let mut dst = vec![0.0; 5000];
+    let mut rdr = &buf[..];
+    rdr.read_f16_into_be(&mut dst).unwrap();
+    assert_eq!(src, dst);
+    rdr.read_bf16_into_ne(&mut dst[..5]).unwrap();
+    let rounded = [bf16_to_f32(f32_to_bf16(1e30)), f32::from_bits(0x3DCD_0000)];
+    assert_eq!(dst[..5], [0.5, -3.0, 256.0, rounded[0], rounded[1]]);
+    assert!(rdr.is_empty());
+}
+
+#[test]
+fn read_write_fixed() {
+    use crate::{BigEndian, Fixed, Q16_16, Q2_14, Q8_8};
+
+    // TrueType: a `Fixed` version number and some `F2Dot14` vector components
+    let data = b"\x00\x01\x80\x00\x40\x00\xC0\x00\x7F\xFF\x80\x00";
+    let mut rdr = &data[..];
+    let version: Q16_16 = rdr.read_fixed_be().unwrap();
+    assert_eq!(1.5, version.to_f64());
+    assert_eq!(0x0001_8000, version.to_bits());
+    let xs = (0..4).map(|_| rdr.read_fixed_be::<i16, 14>().unwrap()).collect::<Vec<_>>();
+    assert_eq!(
+        xs.iter().map(|x| x.to_f64()).collect::<Vec<_>>(),
+        [1.0, -1.0, 2.0 - 2f64.powi(-14), -2.0],
+    );
+
+    let mut buf = vec![];
+    assert_eq!(4, buf.write_fixed_be(version).unwrap());
+    for x in &xs {
+        assert_eq!(2, buf.write_fixed::<BigEndian, _, 14>(*x).unwrap());
+    }
+    assert_eq!(buf, data);
+
+    assert_eq!(0x0180, Q8_8::from_f64(1.5).to_bits());
+    assert_eq!(-0x0180, Q8_8::from_f64(-1.5).to_bits());
+    assert_eq!(0x0001, Q8_8::from_f64(1.0 / 512.0).to_bits());
+    assert_eq!(-0x0001, Q8_8::from_f64(-1.0 / 512.0).to_bits());
+    assert_eq!(0x0000, Q8_8::from_f64(1.0 / 1024.0).to_bits());
+    assert_eq!(i16::MAX, Q8_8::from_f64(1000.0).to_bits());
+    assert_eq!(i16::MIN, Q8_8::from_f64(-1000.0).to_bits());
+    assert_eq!(0, Q2_14::from_f64(f64::NAN).to_bits());
+    assert_eq!(0, Fixed::<u8, 4>::from_f64(-1.0).to_bits());
+    assert_eq!(0xFF, Fixed::<u8, 4>::from_f64(100.0).to_bits());
+    assert_eq!(-0.5, f64::from(Fixed::<i64, 40>::from_f64(-0.5)));
+    assert_eq!("1.5", Q16_16::from_f64(1.5).to_string());
+
+    let mut buf = vec![];
+    buf.write_fixed_le(Fixed::<u64, 32>::from_bits(u64::MAX)).unwrap();
+    buf.write_fixed_ne(Fixed::<u8, 0>::from_f64(7.0)).unwrap();
+    let mut rdr = &buf[..];
+    assert_eq!(u64::MAX, rdr.read_fixed_le::<u64, 32>().unwrap().to_bits());
+    assert_eq!(7.0, rdr.read_fixed_ne::<u8, 0>().unwrap().to_f64());
+    assert!(rdr.read_fixed_le::<i32, 16>().is_err());
+}
+
+#[test]
+fn read_write_odd_widths() {
+    let mut buf = vec![];
+    assert_eq!(3, buf.write_u24_le(0x123456).unwrap());
+    assert_eq!(3, buf.write_u24_be(0x123456).unwrap());
+    assert_eq!(3, buf.write_i24_le(-2).unwrap());
+    assert_eq!(3, buf.write_i24_be(-0x80_0000).unwrap());
+    assert_eq!(6, buf.write_u48_be(0xFFFF_0000_0001).unwrap());
+    assert_eq!(6, buf.write_i48_le(-0x8000_0000_0000).unwrap());
+    assert_eq!(3, buf.write_u24_ne(0xABCDEF).unwrap());
+    assert_eq!(
+        buf[..24],
+        [
+            0x56, 0x34, 0x12, 0x12, 0x34, 0x56, 0xFE, 0xFF, 0xFF, 0x80, 0x00, 0x00,
+            0xFF, 0xFF, 0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x80,
+        ],
+    );
+
+    let mut rdr = &buf[..];
+    assert_eq!(0x123456, rdr.read_u24_le().unwrap());
+    assert_eq!(0x123456, rdr.read_u24_be().unwrap());
+    assert_eq!(-2, rdr.read_i24_le().unwrap());
+    assert_eq!(-0x80_0000, rdr.read_i24_be().unwrap());
+    assert_eq!(0xFFFF_0000_0001, rdr.read_u48_be().unwrap());
+    assert_eq!(-0x8000_0000_0000, rdr.read_i48_le().unwrap());
+    assert_eq!(0xABCDEF, rdr.read_u24_ne().unwrap());
+    assert!(rdr.read_u24_le().is_err());
+
+    // every width, with sign extension from the top bit
+    for n in 1..=16 {
+        let mut buf = vec![];
+        buf.write_uint_le(1, n).unwrap();
+        buf.write_uint_be(1, n).unwrap();
+        buf.write_int_le(-1, n).unwrap();
+        buf.write_int_be(-3, n).unwrap();
+        assert_eq!(4 * n, buf.len());
+        let mut rdr = &buf[..];
+        assert_eq!(1, rdr.read_uint_le(n).unwrap());
+        assert_eq!(1, rdr.read_uint_be(n).unwrap());
+        assert_eq!(-1, rdr.read_int_le(n).unwrap());
+        assert_eq!((u128::MAX >> (128 - 8 * n)) - 2, (&buf[3 * n..]).read_uint_be(n).unwrap());
+        assert_eq!(-3, rdr.read_int_be(n).unwrap());
+    }
+
+    // range checks
+    let mut buf = vec![];
+    assert!(buf.write_u24_le(0x100_0000).is_err());
+    assert!(buf.write_i24_be(0x80_0000).is_err());
+    assert!(buf.write_i24_le(-0x80_0001).is_err());
+    assert!(buf.write_u48_ne(1 << 48).is_err());
+    assert!(buf.write_i48_be(1 << 47).is_err());
+    assert!(buf.write_uint_le(256, 1).is_err());
+    assert!(buf.write_int_be(128, 1).is_err());
+    assert!(buf.write_int_be(-129, 1).is_err());
+    assert!(buf.is_empty());
+    assert_eq!(16, buf.write_uint_be(u128::MAX, 16).unwrap());
+    assert_eq!(16, buf.write_int_le(i128::MIN, 16).unwrap());
+    assert_eq!(1, buf.write_int_le(-128, 1).unwrap());
+    let mut rdr = &buf[..];
+    assert_eq!(u128::MAX, rdr.read_uint_le(16).unwrap());
+    assert_eq!(i128::MIN, rdr.read_int_le(16).unwrap());
+    assert_eq!(-128, rdr.read_int_be(1).unwrap());
+}
+
+#[test]
+fn string_errors() {
+    use crate::{Error, LengthPrefix};
+    use std::io::{self, Cursor};
+
+    // the limit is reported as such, even when the terminator is there
+    let err = Cursor::new(b"Hello, world!\0").read_cstr_utf8(Some(4)).unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded { max: 4, string_offset: Some(4) }));
+    assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    assert_eq!("string exceeds max length of 4 at offset 4", err.to_string());
+
+    let err = Cursor::new(b"\x05abc").read_pstr_utf8(LengthPrefix::U8, Some(4)).unwrap_err();
+    assert!(matches!(err, Error::LimitExceeded { max: 4, string_offset: None }));
+
+    // EOF, with how far in it was hit when that's known
+    let err = Cursor::new(b"abc").read_cstr_utf8(None).unwrap_err();
+    assert!(matches!(err, Error::UnexpectedEof { string_offset: Some(3), .. }));
+    let err = Cursor::new(b"abc").read_cstr_utf8_lossy_fast(None).unwrap_err();
+    assert_eq!(Some(3), err.offset());
+    let err = Cursor::new(b"abc").read_str_utf8(4).unwrap_err();
+    assert!(matches!(err, Error::UnexpectedEof { string_offset: Some(3), .. }));
+    assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    let err = Cursor::new(b"a\0b").read_str_utf16_le(2).unwrap_err();
+    assert!(matches!(err, Error::UnexpectedEof { string_offset: Some(3), .. }));
+    let err = Cursor::new(b"a\0b").read_cstr_utf16_fast_le(None).unwrap_err();
+    assert!(matches!(err, Error::UnexpectedEof { string_offset: Some(3), .. }));
+
+    // `?` into io::Result keeps the kind and the original error
+    fn read(data: &[u8]) -> io::Result<String> {
+        Ok(Cursor::new(data).read_cstr_utf8(None)?)
+    }
+    let err = read(b"\xFF\0").unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    let inner = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+    assert!(matches!(inner, Error::InvalidUtf8 { string_offset: Some(0) }));
+    assert_eq!("invalid UTF-8 at offset 0", err.to_string());
+
+    // and other I/O errors pass through untouched
+    let err = Error::from(io::Error::other("oh no"));
+    assert!(matches!(err, Error::Io(_)));
+    assert_eq!("oh no", io::Error::from(err).to_string());
+
+    // EOF from the reader keeps the original error, and gives it back unchanged
+    let err = Error::from(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated"));
+    assert!(matches!(err, Error::UnexpectedEof { string_offset: None, source: Some(_) }));
+    assert_eq!("truncated", std::error::Error::source(&err).unwrap().to_string());
+    assert_eq!("truncated", io::Error::from(err).to_string());
+
+    // conversions from std's UTF errors
+    let utf8 = String::from_utf8(b"ab\xFF".to_vec()).unwrap_err();
+    assert_eq!(Some(2), Error::from(utf8).offset());
+    let utf16 = String::from_utf16(&[0xD800]).unwrap_err();
+    assert!(matches!(Error::from(utf16), Error::InvalidUtf16 { string_offset: None }));
+}
+
+#[test]
+fn tracked() {
+    use crate::Tracked;
+    use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+
+    let data = b"\x01\x02\x00\x00\x00hello\0world\xFF\xFF\xFF\xFF";
+    let mut rdr = Tracked::new(&data[..]);
+    assert_eq!(1, rdr.read_u8().unwrap());
+    assert_eq!(2, rdr.read_u32_le().unwrap());
+    assert_eq!(5, rdr.position());
+    assert_eq!("hello", rdr.read_cstr_utf8(None).unwrap());
+    assert_eq!(11, rdr.stream_position().unwrap());
+
+    // forward skips work on a plain slice, which isn't `Seek`
+    assert_eq!(13, rdr.seek(SeekFrom::Current(2)).unwrap());
+    assert_eq!("rld", rdr.read_str_utf8(3).unwrap());
+    assert_eq!(17, rdr.seek(SeekFrom::Start(17)).unwrap());
+    let err = rdr.seek(SeekFrom::Current(-1)).unwrap_err();
+    assert_eq!(io::ErrorKind::Unsupported, err.kind());
+    assert_eq!(io::ErrorKind::Unsupported, rdr.seek(SeekFrom::Start(0)).unwrap_err().kind());
+    assert_eq!(io::ErrorKind::Unsupported, rdr.seek(SeekFrom::End(0)).unwrap_err().kind());
+    assert_eq!(io::ErrorKind::UnexpectedEof, rdr.seek(SeekFrom::Current(5)).unwrap_err().kind());
+    assert_eq!(data.len() as u64, rdr.position());
+
+    // so generic seeking code can skip through one, counting from `with_position`
+    let mut rdr = Tracked::with_position(&data[5..], 5);
+    rdr.skip_fast(6).unwrap();
+    assert_eq!(11, rdr.stream_position().unwrap());
+    assert_eq!("world", rdr.read_str_utf8(5).unwrap());
+    // ...but not seek backwards, which peeking needs
+    assert_eq!(io::ErrorKind::Unsupported, rdr.peek_u8().unwrap_err().kind());
+
+    // errors partway through a read leave the position at how far it got
+    let mut rdr = Tracked::with_position(&b"ab"[..], 100);
+    assert!(rdr.read_u32_be().is_err());
+    assert_eq!(102, rdr.position());
+
+    // consuming from a buffered reader is counted too
+    let mut rdr = Tracked::new(BufReader::new(&data[..]));
+    assert_eq!(data.len(), rdr.fill_buf().unwrap().len());
+    rdr.consume(5);
+    assert_eq!(5, rdr.position());
+    assert_eq!(b'h', rdr.read_u8().unwrap());
+    assert_eq!(6, rdr.position());
+
+    let mut wtr = Tracked::new(vec![]);
+    wtr.write_u16_be(0x1234).unwrap();
+    wtr.write_cstr_utf8("hi", None).unwrap();
+    wtr.write_uleb128_u64(300).unwrap();
+    assert_eq!(7, wtr.position());
+    assert_eq!(7, wtr.into_inner().len());
+}
+
+#[test]
+fn peek() {
+    use crate::{Peekable, Tracked};
+    use std::io::{self, BufReader, Cursor, Read, Seek, SeekFrom};
+
+    // format detection on something that isn't `Seek`
+    let data = b"\x89PNG\r\n\x1A\n\x00\x00\x00\x0DIHDR";
+    let mut rdr = Peekable::new(&data[..]);
+    assert_eq!(0x89, rdr.peek_u8().unwrap());
+    assert_eq!(0x8950_4E47, rdr.peek_u32_be().unwrap());
+    assert_eq!(0x474E_5089, rdr.peek_u32_le().unwrap());
+    assert_eq!(b"\x89PNG\r\n\x1A\n", rdr.peek_bytes(8).unwrap());
+    assert_eq!(8, rdr.buffer().len());
+    assert_eq!(0x8950, rdr.read_u16_be().unwrap());
+    assert_eq!(0x4E47, rdr.peek_u16_be().unwrap());
+    assert_eq!(b"NG\r\n\x1A\n\x00\x00", rdr.peek_bytes(8).unwrap());
+    let mut buf = [0u8; 10];
+    rdr.read_exact(&mut buf).unwrap();
+    assert_eq!(b"NG\r\n\x1A\n\x00\x00\x00\x0D", &buf);
+    assert!(rdr.buffer().is_empty());
+    assert_eq!(0x4948_4452, rdr.peek_u32_be().unwrap());
+
+    // running out keeps what was peeked
+    let err = rdr.peek_bytes(5).unwrap_err();
+    assert_eq!(io::ErrorKind::UnexpectedEof, err.kind());
+    assert_eq!(b"IHDR", rdr.buffer());
+    assert_eq!("IHDR", rdr.read_str_utf8(4).unwrap());
+    assert!(rdr.peek_u8().is_err());
+
+    // the seeking versions put the position back, even when the read fails
+    let mut rdr = Cursor::new(&data[..]);
+    rdr.set_position(12);
+    assert_eq!(u32::from_be_bytes(*b"IHDR"), rdr.peek_u32_be().unwrap());
+    assert_eq!(u16::from_le_bytes(*b"IH"), rdr.peek_u16_le().unwrap());
+    let mut buf = [0u8; 2];
+    rdr.peek_exact(&mut buf).unwrap();
+    assert_eq!(b"IH", &buf);
+    assert!(rdr.peek_u64_le().is_err());
+    assert_eq!(12, rdr.position());
+    assert_eq!(b'I', rdr.read_u8().unwrap());
+
+    // including through wrappers which pass seeks on
+    let mut rdr = BufReader::with_capacity(4, Cursor::new(&data[..]));
+    rdr.seek(SeekFrom::Start(12)).unwrap();
+    assert_eq!(u32::from_be_bytes(*b"IHDR"), rdr.peek_u32_be().unwrap());
+    assert!(rdr.peek_u64_be().is_err());
+    assert_eq!(12, rdr.stream_position().unwrap());
+    assert_eq!("IHDR", rdr.read_str_utf8(4).unwrap());
+
+    // but not through ones which can't seek back, where that error is what comes out
+    let mut rdr = Tracked::new(&data[..]);
+    assert_eq!(io::ErrorKind::Unsupported, rdr.peek_u16_be().unwrap_err().kind());
+    assert_eq!(2, rdr.position());
+}
+
+#[test]
+fn buffered_cstr() {
+    use crate::{Error, Peekable, Utf8};
+    use std::io::{BufReader, Cursor, Read};
+
+    // terminators at every offset, including across word and buffer boundaries
+    for len in 0..40 {
+        let mut data = "x".repeat(len).into_bytes();
+        data.extend_from_slice(b"\0after");
+        for &cap in &[1, 3, 8, 64] {
+            let mut rdr = BufReader::with_capacity(cap, &data[..]);
+            assert_eq!(len, rdr.read_cstr_utf8(None).unwrap().len());
+            let mut rest = vec![];
+            rdr.read_to_end(&mut rest).unwrap();
+            assert_eq!(b"after", &rest[..]);
+        }
+        assert_eq!(
+            Cursor::new(&data).read_cstr::<Utf8>(None).unwrap(),
+            (&data[..]).read_cstr_utf8(None).unwrap(),
+        );
+    }
+
+    let mut rdr = &b"Hello, \xFFworld!\0\xFF\0"[..];
+    assert!(matches!(
+        rdr.read_cstr_utf8(None),
+        Err(Error::InvalidUtf8 { string_offset: Some(7) }),
+    ));
+    assert_eq!("�", rdr.read_cstr_utf8_lossy(None).unwrap());
+    assert!(rdr.is_empty());
+
+    // limits behave as in the unbuffered version
+    for max in 0..6 {
+        let data = b"abcd\0";
+        let slow = Cursor::new(data).read_cstr::<Utf8>(Some(max));
+        let mut rdr = BufReader::with_capacity(2, &data[..]);
+        let buf = rdr.read_cstr_utf8(Some(max));
+        assert_eq!(slow.is_ok(), buf.is_ok(), "max {}", max);
+        if let (Err(slow), Err(buf)) = (slow, buf) {
+            assert_eq!(slow.offset(), buf.offset());
+            assert!(matches!(buf, Error::LimitExceeded { .. }));
+        }
+    }
+
+    let err = (&b"abc"[..]).read_cstr_utf8(None).unwrap_err();
+    assert!(matches!(err, Error::UnexpectedEof { string_offset: Some(3), .. }));
+
+    // wrappers pass the buffering through
+    let mut rdr = Peekable::new(&b"\x01one\0two\0"[..]);
+    assert_eq!(1, rdr.peek_u8().unwrap());
+    assert_eq!(1, rdr.read_u8().unwrap());
+    assert_eq!("one", rdr.read_cstr_utf8(None).unwrap());
+    assert_eq!("two", unsafe { rdr.read_cstr_utf8_unchecked(None) }.unwrap());
+    assert!(rdr.read_cstr_utf8(None).is_err());
+}
+
+#[test]
+fn cstr_max_boundaries() {
+    use crate::{Error, Utf8};
+    use std::io::Cursor;
+
+    type Reader = fn(&mut Cursor<&[u8]>, Option<usize>) -> crate::Result<String>;
+    let readers: &[(&str, Reader)] = &[
+        ("read_cstr_utf8", |r, max| r.read_cstr_utf8(max)),
+        ("read_cstr_utf8_lossy", |r, max| r.read_cstr_utf8_lossy(max)),
+        ("read_cstr_utf8_unchecked", |r, max| unsafe { r.read_cstr_utf8_unchecked(max) }),
+        ("read_cstr::<Utf8>", |r, max| r.read_cstr::<Utf8>(max)),
+        ("read_cstr_lossy::<Utf8>", |r, max| r.read_cstr_lossy::<Utf8>(max)),
+        ("read_cstr_utf8_fast", |r, max| r.read_cstr_utf8_fast(max)),
+        ("read_cstr_utf8_lossy_fast", |r, max| r.read_cstr_utf8_lossy_fast(max)),
+        ("read_cstr_utf8_unchecked_fast", |r, max| unsafe { r.read_cstr_utf8_unchecked_fast(max) }),
+    ];
+
+    for len in 0..=10 {
+        let mut data = b"abcdefghijklmnop"[..len].to_vec();
+        data.extend_from_slice(b"\0tail");
+        for max in (0..=12).map(Some).chain(Some(None)) {
+            for (name, read) in readers {
+                let mut rdr = Cursor::new(&data[..]);
+                let result = read(&mut rdr, max);
+                let ctx = format!("{} with len {} and max {:?}", name, len, max);
+                if max.is_none_or(|max| len <= max) {
+                    assert_eq!(data[..len], *result.expect(&ctx).as_bytes(), "{}", ctx);
+                    assert_eq!(len as u64 + 1, rdr.position(), "{}", ctx);
+                } else {
+                    let max = max.unwrap();
+                    match result {
+                        Err(Error::LimitExceeded { max: m, string_offset: offset }) => {
+                            assert_eq!((max, Some(max as u64)), (m, offset), "{}", ctx);
+                        },
+                        other => panic!("{}: expected LimitExceeded, got {:?}", ctx, other),
+                    }
+                    assert_eq!(max as u64 + 1, rdr.position(), "{}", ctx);
+                }
+            }
+        }
+
+        // without a terminator it's always EOF, unless the limit comes first
+        for (name, read) in readers {
+            let mut rdr = Cursor::new(&data[..len]);
+            let result = read(&mut rdr, Some(len));
+            assert!(
+                matches!(result, Err(Error::UnexpectedEof { string_offset: Some(n), .. }) if n == len as u64),
+                "{} with len {}: {:?}",
+                name,
+                len,
+                result,
+            );
+            if len > 0 {
+                let mut rdr = Cursor::new(&data[..len]);
+                let result = read(&mut rdr, Some(len - 1));
+                assert!(matches!(result, Err(Error::LimitExceeded { .. })), "{}", name);
+            }
+        }
+    }
+}
+
+#[test]
+fn read_write_codepages() {
+    use crate::Error;
+    use std::io;
+
+    // a GameMaker-era "ANSI" string
+    let data = b"\x93Caf\xE9 \x80 5\x94\0";
+    assert_eq!("\u{201C}Café € 5\u{201D}", (&data[..]).read_cstr_cp1252(None).unwrap());
+    assert_eq!("\u{93}Café \u{80} 5\u{94}", (&data[..]).read_str_latin1(10).unwrap());
+    assert_eq!("ôCafΘ Ç 5ö", (&data[..]).read_str_cp437(10).unwrap());
+    assert_eq!("░▒▓█ ÿ\u{A0}", (&b"\xB0\xB1\xB2\xDB\x20\x98\xFF"[..]).read_str_cp437(7).unwrap());
+
+    // every byte survives decoding and encoding again
+    let all = (0..=255).collect::<Vec<u8>>();
+    type Codec = (
+        fn(&mut &[u8], usize) -> crate::Result<String>,
+        fn(&mut Vec<u8>, &str) -> io::Result<usize>,
+    );
+    let codecs: [Codec; 3] = [
+        (|r, len| r.read_str_latin1(len), |w, s| w.write_str_latin1(s)),
+        (|r, len| r.read_str_cp1252(len), |w, s| w.write_str_cp1252(s)),
+        (|r, len| r.read_str_cp437(len), |w, s| w.write_str_cp437(s)),
+    ];
+    for (read, write) in codecs.iter() {
+        let s = read(&mut &all[..], all.len()).unwrap();
+        assert_eq!(256, s.chars().count());
+        let mut buf = vec![];
+        assert_eq!(256, write(&mut buf, &s).unwrap());
+        assert_eq!(all, buf);
+    }
+
+    // unmappable characters are reported, and nothing is written
+    let mut buf = vec![];
+    let err = buf.write_str_cp1252("naïve 日本").unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    let inner = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+    assert!(matches!(inner, Error::Unencodable { ch: '日', string_offset: Some(6) }));
+    assert!(buf.write_str_latin1("€").is_err());
+    assert!(buf.write_str_cp437("é€").is_err());
+    assert!(buf.is_empty());
+
+    assert_eq!(7, buf.write_cstr_cp1252("€uro ™", Some(6)).unwrap());
+    assert_eq!(b"\x80uro \x99\0", &buf[..]);
+    assert!(buf.write_cstr_cp1252("€uro ™", Some(5)).is_err());
+    assert!(buf.write_cstr_latin1("a\0b", None).is_err());
+    assert_eq!(2, buf.write_cstr_cp437("Σ", None).unwrap());
+    assert_eq!(2, buf.write_cstr_latin1("ÿ", None).unwrap());
+    let mut rdr = &buf[..];
+    assert_eq!("€uro ™", rdr.read_cstr_cp1252(Some(6)).unwrap());
+    assert_eq!("Σ", rdr.read_cstr_cp437(None).unwrap());
+    assert!(matches!(rdr.read_cstr_latin1(Some(0)), Err(Error::LimitExceeded { .. })));
+}
+
+#[test]
+#[cfg(feature = "cjk")]
+fn read_write_multibyte() {
+    use crate::Error;
+    use std::io;
+
+    // 0x5C is a trail byte here, not a backslash
+    let data = b"\x95\x5C\x8E\xA6\xB1\0\xD6\xD0\xCE\xC4\x80\0\xC7\xD1\xB1\xB9\xBE\xEE\0";
+    let mut rdr = &data[..];
+    assert_eq!("表示ｱ", rdr.read_cstr_shift_jis(None).unwrap());
+    assert_eq!("中文€", rdr.read_cstr_gbk(Some(5)).unwrap());
+    assert_eq!("한국어", rdr.read_cstr_euc_kr(None).unwrap());
+    assert!(rdr.is_empty());
+    assert_eq!("表示", (&data[..]).read_str_shift_jis(4).unwrap());
+
+    // a lead byte right before the terminator is a truncated character, not a pair with it
+    let mut rdr = &b"\x93\xFA\x96\0x\0"[..];
+    assert!(matches!(
+        (&b"\x93\xFA\x96\0"[..]).read_cstr_shift_jis(None),
+        Err(Error::InvalidEncoding { string_offset: Some(2) })
+    ));
+    assert_eq!("日\u{FFFD}", rdr.read_cstr_shift_jis_lossy(None).unwrap());
+    assert_eq!("x", rdr.read_cstr_shift_jis(None).unwrap());
+    assert!(matches!(
+        (&b"ab\xC7\x41"[..]).read_str_euc_kr(4),
+        Err(Error::InvalidEncoding { string_offset: Some(2) })
+    ));
+    assert_eq!("ab\u{FFFD}A", (&b"ab\xC7\x41"[..]).read_str_euc_kr_lossy(4).unwrap());
+    assert!(matches!(
+        (&b"\xD6\xD0\xCE\0"[..]).read_cstr_gbk_lossy(Some(2)),
+        Err(Error::LimitExceeded { max: 2, string_offset: Some(2) })
+    ));
+
+    // round trip
+    let mut buf = vec![];
+    assert_eq!(5, buf.write_str_shift_jis("表示ｱ").unwrap());
+    assert_eq!(6, buf.write_cstr_gbk("中文€", Some(5)).unwrap());
+    assert_eq!(7, buf.write_cstr_euc_kr("한국어", None).unwrap());
+    assert_eq!(&data[..5], &buf[..5]);
+    assert_eq!(&data[6..], &buf[5..]);
+
+    // unmappable characters are reported, and nothing is written
+    let mut buf = vec![];
+    let err = buf.write_str_shift_jis("日本語한").unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    let inner = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+    assert!(matches!(inner, Error::Unencodable { ch: '한', string_offset: Some(6) }));
+    assert!(buf.write_cstr_gbk("中文", Some(3)).is_err());
+    assert!(buf.write_cstr_euc_kr("a\0b", None).is_err());
+    assert!(buf.is_empty());
+}
+
+#[test]
+fn generic_encodings() {
+    use crate::{Cp1252, Encoding, Error, Utf16Be, Utf16Le, Utf8};
+    use std::io::{self, Cursor};
+
+    // a format's own code page: 0x01..=0x1A are 'a'..='z', everything else is invalid
+    enum Alphabet {}
+    impl Encoding for Alphabet {
+        fn decode(bytes: &[u8]) -> crate::Result<String> {
+            match bytes.iter().position(|x| !(0x01..=0x1A).contains(x)) {
+                Some(i) => Err(Error::InvalidEncoding { string_offset: Some(i as u64) }),
+                None => Ok(Self::decode_lossy(bytes)),
+            }
+        }
+
+        fn decode_lossy(bytes: &[u8]) -> String {
+            bytes.iter().map(|&x| match x {
+                0x01..=0x1A => char::from(b'a' + x - 1),
+                _ => char::REPLACEMENT_CHARACTER,
+            }).collect()
+        }
+
+        fn encode(s: &str) -> crate::Result<Vec<u8>> {
+            s.chars().enumerate().map(|(i, ch)| match ch {
+                'a'..='z' => Ok(ch as u8 - b'a' + 1),
+                _ => Err(Error::Unencodable { ch, string_offset: Some(i as u64) }),
+            }).collect()
+        }
+    }
+
+    let mut buf = vec![];
+    assert_eq!(6, buf.write_cstr::<Alphabet>("hello", Some(5)).unwrap());
+    assert_eq!(b"\x08\x05\x0C\x0C\x0F\0", &buf[..]);
+    assert!(buf.write_cstr::<Alphabet>("hello", Some(4)).is_err());
+    let err = buf.write_str::<Alphabet>("hi!").unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+    assert_eq!(6, buf.len());
+    buf.extend_from_slice(b"\x08\xFF\x09\0");
+    let mut rdr = &buf[..];
+    assert_eq!("hel", rdr.read_str::<Alphabet>(3).unwrap());
+    assert_eq!("lo", rdr.read_cstr::<Alphabet>(None).unwrap());
+    assert!(matches!(
+        (&buf[6..]).read_cstr::<Alphabet>(None),
+        Err(Error::InvalidEncoding { string_offset: Some(1) })
+    ));
+    assert_eq!("h\u{FFFD}i", rdr.read_cstr_lossy::<Alphabet>(Some(3)).unwrap());
+
+    // wider terminators, which have to be a whole aligned unit of zeroes
+    let mut buf = vec![];
+    assert_eq!(6, buf.write_cstr::<Utf16Le>("\u{100}A", None).unwrap());
+    assert_eq!(b"\x00\x01A\x00\x00\x00", &buf[..]);
+    assert_eq!(4, buf.write_str::<Utf16Be>("\u{100}A").unwrap());
+    let mut rdr = Cursor::new(&buf[..]);
+    assert_eq!("\u{100}A", rdr.read_cstr_fast::<Utf16Le>(Some(2)).unwrap());
+    assert_eq!(6, rdr.position());
+    assert_eq!("\u{100}A", rdr.read_str::<Utf16Be>(2).unwrap());
+    assert!(matches!(
+        (&buf[..]).read_cstr::<Utf16Le>(Some(1)),
+        Err(Error::LimitExceeded { max: 1, string_offset: Some(2) })
+    ));
+    assert!(matches!(
+        (&b"\x00\xD8\0\0"[..]).read_cstr::<Utf16Le>(None),
+        Err(Error::InvalidUtf16 { string_offset: Some(0) })
+    ));
+    assert_eq!("\u{FFFD}", (&b"\x00\xD8\0\0"[..]).read_cstr_lossy::<Utf16Le>(None).unwrap());
+    let mut rdr = Cursor::new(b"\x00\xD8A\0\0\0");
+    assert_eq!("\u{FFFD}A", rdr.read_cstr_lossy_fast::<Utf16Le>(None).unwrap());
+    assert_eq!(6, rdr.position());
+    assert!(matches!(
+        (&b"\x00\x01A"[..]).read_cstr::<Utf16Le>(None),
+        Err(Error::UnexpectedEof { string_offset: Some(3), .. })
+    ));
+
+    // the built-in encodings agree with their specific methods
+    assert_eq!("caf\u{E9}", (&b"caf\xC3\xA9"[..]).read_str::<Utf8>(5).unwrap());
+    assert_eq!("\u{20AC}5", (&b"\x805\0"[..]).read_cstr::<Cp1252>(None).unwrap());
+}
+
+#[test]
+fn wide_strings() {
+    use crate::Error;
+    use std::io::Cursor;
+
+    // zero bytes inside a unit don't end the string
+    let mut buf = vec![];
+    assert_eq!(10, buf.write_cstr_utf16_le("Hé\u{1F600}", Some(4)).unwrap());
+    assert_eq!(b"H\0\xE9\0\x3D\xD8\x00\xDE\0\0", &buf[..]);
+    assert_eq!(6, buf.write_cstr_utf16_be("Hé", None).unwrap());
+    assert!(buf.write_cstr_utf16_le("Hé\u{1F600}", Some(3)).is_err());
+    assert!(buf.write_cstr_utf16_be("a\0", None).is_err());
+    let mut rdr = &buf[..];
+    assert_eq!("Hé\u{1F600}", rdr.read_cstr_utf16_le(Some(4)).unwrap());
+    assert_eq!("Hé", rdr.read_cstr_utf16_be(None).unwrap());
+    assert!(rdr.is_empty());
+    let mut rdr = Cursor::new(&buf[..]);
+    assert_eq!("Hé\u{1F600}", rdr.read_cstr_utf16_fast_le(None).unwrap());
+    assert_eq!("Hé", rdr.read_cstr_utf16_lossy_fast_be(Some(2)).unwrap());
+    assert_eq!(16, rdr.position());
+    assert!(matches!(
+        (&buf[..]).read_cstr_utf16_le(Some(3)),
+        Err(Error::LimitExceeded { max: 3, string_offset: Some(6) })
+    ));
+    assert!(matches!(
+        Cursor::new(&buf[..]).read_cstr_utf16_fast_le(Some(3)),
+        Err(Error::LimitExceeded { max: 3, string_offset: Some(6) })
+    ));
+
+    // an unpaired surrogate right before the terminator
+    let bad = b"H\0\x3D\xD8\0\0";
+    assert!(matches!(
+        (&bad[..]).read_cstr_utf16_le(None),
+        Err(Error::InvalidUtf16 { string_offset: Some(2) })
+    ));
+    assert_eq!("H\u{FFFD}", (&bad[..]).read_cstr_utf16_lossy_le(None).unwrap());
+    assert_eq!("H\u{FFFD}", Cursor::new(&bad[..]).read_cstr_utf16_lossy_fast_le(None).unwrap());
+
+    // UTF-32
+    let mut buf = vec![];
+    assert_eq!(8, buf.write_str_utf32_le("H\u{1F600}").unwrap());
+    assert_eq!(12, buf.write_cstr_utf32_be("é\u{1F600}", Some(2)).unwrap());
+    assert!(buf.write_cstr_utf32_be("é\u{1F600}", Some(1)).is_err());
+    assert_eq!(b"H\0\0\0\x00\xF6\x01\x00\0\0\0\xE9\x00\x01\xF6\x00\0\0\0\0", &buf[..]);
+    let mut rdr = &buf[..];
+    assert_eq!("H\u{1F600}", rdr.read_str_utf32_le(2).unwrap());
+    assert_eq!("é\u{1F600}", rdr.read_cstr_utf32_be(None).unwrap());
+    assert!(matches!(
+        (&b"H\0\0\0\0\xD8\0\0"[..]).read_str_utf32_le(2),
+        Err(Error::InvalidUtf32 { string_offset: Some(4) })
+    ));
+    assert!(matches!(
+        (&b"\0\0\x11\0\0\0\0\0"[..]).read_cstr_utf32_le(None),
+        Err(Error::InvalidUtf32 { string_offset: Some(0) })
+    ));
+}
+
+#[test]
+fn fixed_width_strings() {
+    use crate::{Error, Padding};
+
+    // C leaves whatever was in memory after the terminator
+    let field = b"hero\0\xFFgarbage\0";
+    assert_eq!("hero", (&field[..]).read_fixed_str_utf8(14, Padding::Null).unwrap());
+    assert!(matches!(
+        (&field[..]).read_fixed_str_utf8(14, Padding::Zeroes),
+        Err(Error::InvalidPadding { padding_offset: Some(5) })
+    ));
+    assert_eq!("", (&b"\0\0\0\0"[..]).read_fixed_str_utf8(4, Padding::Zeroes).unwrap());
+    assert_eq!("a b", (&b"a b   "[..]).read_fixed_str_utf8(6, Padding::Space).unwrap());
+    assert_eq!("a", (&b"a \0xx"[..]).read_fixed_str_utf8(5, Padding::Space).unwrap());
+    assert!(matches!(
+        (&b"\xFFok\0"[..]).read_fixed_str_utf8(4, Padding::Null),
+        Err(Error::InvalidUtf8 { string_offset: Some(0) })
+    ));
+    assert!(matches!(
+        (&b"abc"[..]).read_fixed_str_utf8(4, Padding::Null),
+        Err(Error::UnexpectedEof { .. })
+    ));
+
+    let mut buf = vec![];
+    assert_eq!(8, buf.write_fixed_str_utf8("hero", 8, Padding::Zeroes).unwrap());
+    assert_eq!(4, buf.write_fixed_str_utf8("full", 4, Padding::Null).unwrap());
+    assert_eq!(6, buf.write_fixed_str_utf8("é", 6, Padding::Space).unwrap());
+    assert_eq!(b"hero\0\0\0\0full\xC3\xA9    ", &buf[..]);
+    assert!(buf.write_fixed_str_utf8("toolong", 6, Padding::Null).is_err());
+    assert!(buf.write_fixed_str_utf8("a\0b", 6, Padding::Null).is_err());
+    assert_eq!(18, buf.len());
+
+    let mut rdr = &buf[..];
+    assert_eq!("hero", rdr.read_fixed_str_utf8(8, Padding::Zeroes).unwrap());
+    assert_eq!("full", rdr.read_fixed_str_utf8(4, Padding::Zeroes).unwrap());
+    assert_eq!("é", rdr.read_fixed_str_utf8(6, Padding::Space).unwrap());
+    assert!(rdr.is_empty());
+}
+
+#[test]
+fn padding_and_alignment() {
+    use crate::{Error, Tracked};
+    use std::io::{self, Cursor};
+
+    let data = b"\x07\0\0\0\x01\x02\0\0\0\0\0\0\0\0\0\0\xAA";
+    let mut rdr = &data[..];
+    rdr.skip(4).unwrap();
+    assert_eq!(1, rdr.read_u8().unwrap());
+    assert_eq!(io::ErrorKind::UnexpectedEof, rdr.skip(10000).unwrap_err().kind());
+
+    let mut rdr = Cursor::new(&data[..]);
+    rdr.skip_fast(5).unwrap();
+    assert_eq!(2, rdr.read_u8().unwrap());
+    rdr.expect_zero_padding(10).unwrap();
+    assert_eq!(16, rdr.position());
+    rdr.set_position(1);
+    let err = rdr.expect_zero_padding(5).unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidData, err.kind());
+    let inner = err.get_ref().unwrap().downcast_ref::<Error>().unwrap();
+    assert!(matches!(inner, Error::InvalidPadding { padding_offset: Some(3) }));
+
+    // alignment is counted from wherever the tracked position says the stream is
+    let mut rdr = Tracked::new(&data[..]);
+    assert_eq!(0, rdr.align_to(4).unwrap());
+    assert_eq!(7, rdr.read_u8().unwrap());
+    assert_eq!(3, rdr.align_to(4).unwrap());
+    assert_eq!(1, rdr.read_u8().unwrap());
+    assert_eq!(11, rdr.align_to(16).unwrap());
+    assert_eq!(0xAA, rdr.read_u8().unwrap());
+    assert!(rdr.align_to(8).is_err());
+    let mut rdr = Tracked::with_position(&data[..], 6);
+    assert_eq!(2, rdr.align_to(8).unwrap());
+    assert_eq!(0, rdr.read_u8().unwrap());
+
+    // seekable readers can align by seeking instead
+    let mut rdr = Cursor::new(&data[..]);
+    rdr.set_position(5);
+    assert_eq!(3, rdr.align_to(8).unwrap());
+    assert_eq!(0, rdr.align_to(8).unwrap());
+    assert_eq!(8, rdr.position());
+    let mut rdr = Tracked::new(Cursor::new(&data[..]));
+    rdr.read_u8().unwrap();
+    assert_eq!(15, rdr.align_to_fast(16).unwrap());
+    assert_eq!(16, rdr.get_ref().position());
+    assert_eq!(0xAA, rdr.read_u8().unwrap());
+    assert_eq!(7, rdr.align_to_fast(8).unwrap());
+    assert_eq!(24, rdr.position());
+
+    let mut wtr = Tracked::new(vec![]);
+    assert_eq!(0, wtr.pad_to_alignment(8).unwrap());
+    wtr.write_u8(0x07).unwrap();
+    assert_eq!(3, wtr.pad_to_alignment(4).unwrap());
+    wtr.write_u16_le(0x0201).unwrap();
+    assert_eq!(10, wtr.pad_to_alignment(16).unwrap());
+    assert_eq!(1, wtr.write_padding(1, 0xAA).unwrap());
+    assert_eq!(&data[..], &wtr.get_ref()[..]);
+    assert_eq!(5000, wtr.write_padding(5000, b' ').unwrap());
+    assert_eq!(5017, wtr.position());
 }
@@ -0,0 +1,175 @@
+use crate::{_padding_len, io, ReadPrimitives, WritePrimitives};
+use core::convert::TryFrom;
+
+/// Wraps a reader or writer and counts the bytes passing through it, for knowing offsets into
+/// streams which aren't `io::Seek` (such as pipes and decompressors).
+///
+/// Readers also get an `io::Seek` implementation which can only skip forwards, by reading and
+/// discarding bytes, and reports the counted position as `stream_position` without touching
+/// the underlying reader. That isn't enough for the `_fast` string readers,
+/// which need to seek backwards.
+#[derive(Clone, Debug, Default)]
+pub struct Tracked<T> {
+    inner: T,
+    pos: u64,
+}
+
+impl<T> Tracked<T> {
+    /// Creates a new wrapper over `inner`, counting from 0.
+    pub fn new(inner: T) -> Self {
+        Self::with_position(inner, 0)
+    }
+
+    /// Creates a new wrapper over `inner`, counting from `pos`,
+    /// for streams which have already been partly read or written.
+    pub fn with_position(inner: T, pos: u64) -> Self {
+        Self { inner, pos }
+    }
+
+    /// Returns how many bytes have been read or written so far,
+    /// plus the position this was created at.
+    pub fn position(&self) -> u64 {
+        self.pos
+    }
+
+    /// Returns a reference to the underlying reader or writer.
+    pub fn get_ref(&self) -> &T {
+        &self.inner
+    }
+
+    /// Returns a mutable reference to the underlying reader or writer.
+    ///
+    /// Anything read or written through this isn't counted.
+    pub fn get_mut(&mut self) -> &mut T {
+        &mut self.inner
+    }
+
+    /// Returns the underlying reader or writer.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+impl<R> Tracked<R>
+where
+    R: io::Read,
+{
+    /// Skips ahead to the next position that's a multiple of `align`,
+    /// returning how many bytes were skipped.
+    ///
+    /// If the reader runs out first, this fails with `io::ErrorKind::UnexpectedEof`.
+    ///
+    /// # Panics
+    /// Panics if `align` is 0.
+    pub fn align_to(&mut self, align: usize) -> io::Result<usize> {
+        let pad = _padding_len(self.pos, align);
+        self.skip(pad as u64).map(|()| pad)
+    }
+}
+
+impl<R> Tracked<R>
+where
+    R: io::Read + io::Seek,
+{
+    /// Like [align_to](#method.align_to), but seeks the underlying reader forwards
+    /// instead of reading and discarding bytes.
+    ///
+    /// *Unlike [align_to](#method.align_to), this doesn't notice if that goes past the end,
+    /// as seeking there is allowed. The next read will fail instead.*
+    ///
+    /// # Panics
+    /// Panics if `align` is 0.
+    pub fn align_to_fast(&mut self, align: usize) -> io::Result<usize> {
+        let pad = _padding_len(self.pos, align);
+        self.inner.seek(io::SeekFrom::Current(pad as i64))?;
+        self.pos += pad as u64;
+        Ok(pad)
+    }
+}
+
+impl<W> Tracked<W>
+where
+    W: io::Write,
+{
+    /// Writes null bytes up to the next position that's a multiple of `align`,
+    /// returning how many were written.
+    ///
+    /// # Panics
+    /// Panics if `align` is 0.
+    pub fn pad_to_alignment(&mut self, align: usize) -> io::Result<usize> {
+        self.write_padding(_padding_len(self.pos, align), 0x00)
+    }
+}
+
+impl<R> io::Read for Tracked<R>
+where
+    R: io::Read,
+{
+    #[inline]
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+}
+
+impl<R> io::BufRead for Tracked<R>
+where
+    R: io::BufRead,
+{
+    #[inline]
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    #[inline]
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.pos += amt as u64;
+    }
+}
+
+/// Only seeking forwards is supported, with `SeekFrom::Current` or a `SeekFrom::Start` that
+/// isn't behind the current position. Anything else fails with `io::ErrorKind::Unsupported`.
+/// Skipping past the end of the stream fails with `io::ErrorKind::UnexpectedEof`.
+impl<R> io::Seek for Tracked<R>
+where
+    R: io::Read,
+{
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        let n = match pos {
+            io::SeekFrom::Current(n) => u64::try_from(n).ok(),
+            io::SeekFrom::Start(n) => n.checked_sub(self.pos),
+            io::SeekFrom::End(_) => None,
+        };
+        match n {
+            Some(n) => self.skip(n).map(|()| self.pos),
+            None => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "can only seek forwards in a tracked reader",
+            )),
+        }
+    }
+
+    #[inline]
+    fn stream_position(&mut self) -> io::Result<u64> {
+        Ok(self.pos)
+    }
+}
+
+impl<W> io::Write for Tracked<W>
+where
+    W: io::Write,
+{
+    #[inline]
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.pos += n as u64;
+        Ok(n)
+    }
+
+    #[inline]
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}